@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+use rkyv::{Archive, Deserialize, Infallible, Serialize};
+
+use crate::config::{Config, PipelineStep};
+
+/// Directory (relative to the run's output root) holding the checkpoint
+/// state file, shared with [`crate::manifest::MANIFEST_DIR`] since both
+/// are "resume this run" bookkeeping for the same `.isopipe/` folder.
+pub const CHECKPOINT_DIR: &str = crate::manifest::MANIFEST_DIR;
+
+/// Name of the archived run-state file inside [`CHECKPOINT_DIR`].
+pub const RUN_STATE_FILE: &str = "run_state.rkyv";
+
+/// Zero-copy, whole-run checkpoint state, distinct from
+/// [`crate::manifest::Manifest`]'s per-step freshness digests: this
+/// records a run's overall progress (which steps finished, in what
+/// order, against which config) so `--resume` can reconstruct it by
+/// `mmap`-ing the file back instead of re-parsing every step's inputs.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let mut state = RunState::new(&config);
+/// state.record(&PipelineStep::Ccs, &step_output_dir);
+/// state.save(&global_output_dir);
+/// ```
+#[derive(Archive, Serialize, Deserialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct RunState {
+    pub run_id: String,
+    pub config_hash: u64,
+    pub completed: Vec<String>,
+    pub step_hashes: HashMap<String, String>,
+}
+
+impl RunState {
+    /// Path to the checkpoint file for a given run's output root.
+    fn path(global_output_dir: &Path) -> PathBuf {
+        global_output_dir.join(CHECKPOINT_DIR).join(RUN_STATE_FILE)
+    }
+
+    /// Start a fresh, empty run state for `config`.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            run_id: config.get_run_id(),
+            config_hash: hash_config(config),
+            completed: Vec::new(),
+            step_hashes: HashMap::new(),
+        }
+    }
+
+    /// Record that `step` has completed, hashing its output directory's
+    /// contents so a later resume can tell whether it was tampered with
+    /// since this checkpoint was written.
+    pub fn record(&mut self, step: &PipelineStep, step_output_dir: &Path) {
+        self.completed.push(step.to_unique_str());
+        self.step_hashes
+            .insert(step.to_unique_str(), hash_dir(step_output_dir));
+    }
+
+    /// Serialize this state as a zero-copy rkyv archive and write it to
+    /// `global_output_dir`'s checkpoint file.
+    pub fn save(&self, global_output_dir: &Path) {
+        let dir = global_output_dir.join(CHECKPOINT_DIR);
+        std::fs::create_dir_all(&dir).expect("ERROR: Could not create .isopipe directory!");
+
+        let bytes = rkyv::to_bytes::<_, 1024>(self)
+            .expect("ERROR: Could not archive run state!");
+
+        std::fs::write(Self::path(global_output_dir), &bytes)
+            .expect("ERROR: Could not write run_state.rkyv!");
+    }
+
+    /// Load and validate a previously-archived run state by `mmap`-ing it
+    /// back and checking it in place, instead of re-parsing a text
+    /// format: a process killed mid-write fails `check_archived_root`'s
+    /// bytes validation rather than silently loading a truncated state.
+    pub fn load(global_output_dir: &Path) -> Result<Self, String> {
+        let path = Self::path(global_output_dir);
+
+        let file = std::fs::File::open(&path)
+            .map_err(|e| format!("ERROR: No checkpoint at {}: {}", path.display(), e))?;
+
+        // SAFETY: the checkpoint file is only ever written by `save` and
+        // never mutated concurrently with a `load` of the same run.
+        let mmap = unsafe {
+            Mmap::map(&file)
+                .map_err(|e| format!("ERROR: Could not mmap {}: {}", path.display(), e))?
+        };
+
+        let archived = rkyv::check_archived_root::<Self>(&mmap)
+            .map_err(|e| format!("ERROR: Corrupt run state at {}: {}", path.display(), e))?;
+
+        archived
+            .deserialize(&mut Infallible)
+            .map_err(|_: std::convert::Infallible| {
+                unreachable!("ERROR: Infallible deserialization of run state failed")
+            })
+    }
+}
+
+/// Stable hash over a `Config`'s raw TOML source, used by
+/// [`Config::resume`] to detect whether the config file changed since
+/// the run being resumed was last checkpointed.
+pub(crate) fn hash_config(config: &Config) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    config.raw.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Stable hash over a directory's file listing (path + mtime + size),
+/// the same fingerprint shape [`crate::manifest::compute_digest`] uses
+/// for a step's inputs.
+fn hash_dir(dir: &Path) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut entries: Vec<String> = std::fs::read_dir(dir)
+        .map(|read| {
+            read.flatten()
+                .filter_map(|entry| {
+                    let meta = entry.metadata().ok()?;
+                    let mtime = meta
+                        .modified()
+                        .ok()?
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .ok()?
+                        .as_secs();
+
+                    Some(format!(
+                        "{}:{}:{}",
+                        entry.path().display(),
+                        mtime,
+                        meta.len()
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort();
+
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
@@ -22,6 +22,16 @@ pub struct Args {
         default_value = "para"
     )]
     pub manager: ParallelManager,
+
+    #[arg(
+        short = 'j',
+        long = "jobs",
+        help = "Maximum number of external tool subprocesses running at once across the whole step DAG (GNU make jobserver protocol; enforced by the local manager, para/nextflow only receive MAKEFLAGS)",
+        value_name = "N",
+        required = false,
+        default_value = "0"
+    )]
+    pub jobs: usize,
 }
 
 impl Args {}
@@ -43,6 +53,11 @@ pub enum SubArgs {
         #[command(flatten)]
         args: WriteArgs,
     },
+    #[command(name = "watch")]
+    Watch {
+        #[command(flatten)]
+        args: WatchArgs,
+    },
 }
 
 /// Run the pipeline from start to finish
@@ -72,6 +87,21 @@ pub struct RunArgs {
         default_value = "config.toml"
     )]
     pub config: PathBuf,
+
+    #[arg(
+        long = "force",
+        visible_alias = "no-cache",
+        help = "Ignore the checkpoint manifest and re-run every step"
+    )]
+    pub force: bool,
+
+    #[arg(
+        long = "resume",
+        help = "Resume a previous run from its checkpointed output directory instead of starting a fresh one",
+        value_name = "OUTPUT_DIR",
+        required = false
+    )]
+    pub resume: Option<PathBuf>,
 }
 
 // impl ArgCheck for RunArgs {}
@@ -174,6 +204,21 @@ pub struct StepArgs {
 
     #[arg(short = 'q', long = "quiet", help = "Decrease verbosity")]
     pub quiet: bool,
+
+    #[arg(
+        long = "force",
+        visible_alias = "no-cache",
+        help = "Ignore the checkpoint manifest and re-run every selected step"
+    )]
+    pub force: bool,
+
+    #[arg(
+        long = "resume",
+        help = "Resume a previous run from its checkpointed output directory instead of starting a fresh one",
+        value_name = "OUTPUT_DIR",
+        required = false
+    )]
+    pub resume: Option<PathBuf>,
 }
 
 impl StepArgs {
@@ -202,6 +247,32 @@ impl StepArgs {
     /// assert_eq!(steps.len(), 7);
     /// ```
     pub fn abs_steps(&self) -> Result<Vec<PipelineStep>, Box<dyn std::error::Error>> {
+        self.abs_steps_with_aliases(&std::collections::HashMap::new())
+    }
+
+    /// Build an absolute list of steps to run based on args, resolving
+    /// any token that isn't a plain step number/name against `aliases`
+    /// (a `[aliases]` table from `config.toml`) before falling back to
+    /// the built-in sort/validate/dedup logic.
+    ///
+    /// # Arguments
+    ///
+    /// * `aliases` - A map of alias name to the list of step tokens it
+    ///   expands to. Aliases may reference other aliases; cycles are
+    ///   rejected.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// let mut aliases = std::collections::HashMap::new();
+    /// aliases.insert("preprocess".to_string(), vec!["0".into(), "1".into(), "2".into()]);
+    ///
+    /// let steps = args.abs_steps_with_aliases(&aliases).unwrap();
+    /// ```
+    pub fn abs_steps_with_aliases(
+        &self,
+        aliases: &std::collections::HashMap<String, Vec<String>>,
+    ) -> Result<Vec<PipelineStep>, Box<dyn std::error::Error>> {
         let max_step = MAX_STEP
             .parse::<usize>()
             .expect("ERROR: Could not parse max step!");
@@ -212,6 +283,50 @@ impl StepArgs {
                 .map_err(|_| format!("ERROR: invalid step '{}'", step).into())
         }
 
+        fn expand_token(
+            token: &str,
+            aliases: &std::collections::HashMap<String, Vec<String>>,
+            seen: &mut std::collections::HashSet<String>,
+        ) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+            if let Ok(step) = parse_step(token) {
+                return Ok(vec![step]);
+            }
+
+            match aliases.get(token) {
+                Some(expansion) => {
+                    if !seen.insert(token.to_string()) {
+                        return Err(format!("ERROR: alias cycle detected at '{}'", token).into());
+                    }
+
+                    let mut steps = Vec::new();
+                    for nested in expansion {
+                        steps.extend(expand_token(nested, aliases, seen)?);
+                    }
+
+                    Ok(steps)
+                }
+                None => Err(format!("ERROR: invalid step '{}'", token).into()),
+            }
+        }
+
+        fn expand_single(
+            token: &str,
+            aliases: &std::collections::HashMap<String, Vec<String>>,
+            flag: &str,
+        ) -> Result<usize, Box<dyn std::error::Error>> {
+            let mut seen = std::collections::HashSet::new();
+            let expanded = expand_token(token, aliases, &mut seen)?;
+
+            match expanded.as_slice() {
+                [single] => Ok(*single),
+                _ => Err(format!(
+                    "ERROR: --{} alias '{}' must expand to exactly one step",
+                    flag, token
+                )
+                .into()),
+            }
+        }
+
         fn validate_step(
             step: usize,
             max: usize,
@@ -225,8 +340,8 @@ impl StepArgs {
             Ok(step)
         }
 
-        let from = validate_step(parse_step(&self.from)?, max_step, "from")?;
-        let to = validate_step(parse_step(&self.to)?, max_step, "to")?;
+        let from = validate_step(expand_single(&self.from, aliases, "from")?, max_step, "from")?;
+        let to = validate_step(expand_single(&self.to, aliases, "to")?, max_step, "to")?;
 
         if from > to {
             return Err("ERROR: --from must be less than --to".into());
@@ -237,9 +352,17 @@ impl StepArgs {
         if let Some(only) = &self.only {
             let mut steps: Vec<usize> = only
                 .iter()
-                .map(|s| validate_step(parse_step(s)?, max_step, "only"))
+                .map(|s| {
+                    let mut seen = std::collections::HashSet::new();
+                    expand_token(s, aliases, &mut seen)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .map(|s| validate_step(s, max_step, "only"))
                 .collect::<Result<Vec<_>, _>>()?;
             steps.sort_unstable();
+            steps.dedup();
 
             log::info!("INFO: running step/s {:?} only...", steps);
 
@@ -256,7 +379,14 @@ impl StepArgs {
 
         let skips = if let Some(skip) = &self.skip {
             skip.iter()
-                .map(|s| validate_step(parse_step(s)?, max_step, "skip"))
+                .map(|s| {
+                    let mut seen = std::collections::HashSet::new();
+                    expand_token(s, aliases, &mut seen)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .map(|s| validate_step(s, max_step, "skip"))
                 .collect::<Result<Vec<_>, _>>()?
         } else {
             Vec::new()
@@ -322,3 +452,50 @@ pub struct WriteArgs {
     )]
     pub cmd: Vec<String>,
 }
+
+/// Watch the configured input directories and re-run the affected steps
+/// (plus everything downstream) whenever their inputs change.
+///
+/// # Example
+///
+/// ```bash,no_run
+/// isopipe watch -c config.toml --debounce 200
+/// ```
+///
+/// # Arguments
+///
+/// * `config` - Path to the configuration file
+/// * `debounce` - Milliseconds to coalesce filesystem events over before triggering a run
+///
+/// # Note
+///
+/// * Writes inside the pipeline's own output directories are ignored to
+///   avoid feedback loops.
+/// * A change detected mid-run queues exactly one follow-up run.
+#[derive(Debug, Parser, Clone)]
+pub struct WatchArgs {
+    #[arg(
+        short = 'c',
+        long = "config",
+        help = "Path to the configuration file",
+        value_name = "STEP",
+        required = true,
+        default_value = "config.toml"
+    )]
+    pub config: PathBuf,
+
+    #[arg(
+        long = "debounce",
+        help = "Milliseconds to coalesce filesystem events over before triggering a run",
+        value_name = "MS",
+        default_value = "200"
+    )]
+    pub debounce: u64,
+
+    #[arg(
+        long = "new-inputs",
+        help = "Only react to newly-arrived BAM files in the first step's input_dir, instead of re-running every affected downstream step",
+        default_value = "false"
+    )]
+    pub new_inputs: bool,
+}
@@ -2,6 +2,7 @@ use log::{error, info};
 use serde::Deserialize;
 
 use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -44,10 +45,57 @@ pub struct Config {
     pub metadata: HashMap<String, String>,
     pub packages: HashMap<String, String>,
     pub global: HashMap<String, ParamValue>,
-    #[serde(default, deserialize_with = "deserialize_steps")]
+    /// Raw `steps = "ccs,lima,..."` tokens, each either a plain step
+    /// name/number or a `[aliases]` macro; resolved into `steps` by
+    /// `Config::expand_step_tokens` once the whole config (and therefore
+    /// `aliases`) is available.
+    #[serde(rename = "steps", default, deserialize_with = "deserialize_step_tokens")]
+    pub step_tokens: Vec<String>,
+    #[serde(skip)]
     pub steps: Vec<PipelineStep>,
     #[serde(default, deserialize_with = "deserialize_to_hash")]
     pub params: HashMap<PipelineStep, StepParams>,
+    /// User-defined step aliases/phases, e.g. `preprocess = [0, 1, 2]`.
+    /// Resolved against `StepArgs::abs_steps_with_aliases`.
+    #[serde(default, deserialize_with = "deserialize_aliases")]
+    pub aliases: HashMap<String, Vec<String>>,
+    /// Regex with a named `group` capture used by
+    /// `core::samtools::scan_groups` to extract a merge group name from a
+    /// BAM's basename, instead of the hardcoded `split(".").nth(1)`. Lets
+    /// users adapt to naming conventions other than `{prefix}.{name}.{*}.bam`
+    /// without recompiling.
+    #[serde(default = "default_group_pattern")]
+    pub group_pattern: String,
+    /// When `true`, `core::samtools::merge` relocates a group's unmerged
+    /// source BAMs into an `unmerged/` subdirectory of `input_dir` instead
+    /// of deleting them once the merge is verified. Off by default to
+    /// preserve today's destructive behavior.
+    #[serde(default)]
+    pub keep_sources: bool,
+    /// Sort order of `merge`'s BAM inputs: `"coordinate"` (default) or
+    /// `"name"`. See `core::samtools::MergeSortOrder`.
+    #[serde(default = "default_merge_sort_order")]
+    pub merge_sort_order: String,
+    /// Whether `core::samtools::merge` passes `-c`/`-p` to `samtools merge`
+    /// to combine identical `@RG`/`@PG` headers across inputs.
+    #[serde(default)]
+    pub merge_combine_headers: bool,
+    /// The raw `config.toml` source, kept around so that diagnostic
+    /// errors can point at the offending line/column. Not part of the
+    /// schema itself.
+    #[serde(skip)]
+    pub raw: String,
+}
+
+/// Default value for [`Config::merge_sort_order`].
+fn default_merge_sort_order() -> String {
+    String::from("coordinate")
+}
+
+/// Default value for [`Config::group_pattern`]: matches the historical
+/// `{prefix}.{name}.{*}.bam` convention.
+fn default_group_pattern() -> String {
+    DEFAULT_GROUP_PATTERN.to_string()
 }
 
 impl Config {
@@ -71,7 +119,9 @@ impl Config {
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
-        let config: Config = toml::from_str(&contents)?;
+        let mut config: Config = toml::from_str(&contents)?;
+        config.raw = contents;
+        config.expand_step_tokens()?;
 
         Ok(config)
     }
@@ -92,8 +142,15 @@ impl Config {
             metadata: HashMap::new(),
             packages: HashMap::new(),
             global: HashMap::new(),
+            step_tokens: Vec::new(),
             steps: Vec::new(),
             params: HashMap::new(),
+            aliases: HashMap::new(),
+            group_pattern: default_group_pattern(),
+            keep_sources: false,
+            merge_sort_order: default_merge_sort_order(),
+            merge_combine_headers: false,
+            raw: String::new(),
         }
     }
 
@@ -244,8 +301,10 @@ impl Config {
                     return PipelineStep::Ccs == s;
                 }
 
-                s == PipelineStep::from_str(&pkg)
-                    .expect("ERROR: Could not parse step from package name!")
+                s == PipelineStep::from_str(&pkg).unwrap_or_else(|e| {
+                    log::error!("{}", e);
+                    std::process::exit(1);
+                })
             }) {
                 self.packages.remove(pkg);
             }
@@ -361,7 +420,7 @@ impl Config {
     /// ```
     pub fn aware(&mut self, args: StepArgs) -> &mut Self {
         let steps = args
-            .abs_steps()
+            .abs_steps_with_aliases(&self.aliases)
             .expect("ERROR: An error ocurred while materializing steps!");
 
         self.set_steps(steps);
@@ -372,6 +431,36 @@ impl Config {
         self
     }
 
+    /// Resolve `step_tokens` (the raw `steps = "..."` tokens) into
+    /// `steps`, splicing in any `[aliases]` macro's expansion in place of
+    /// its token -- the same alias-cycle-detection logic
+    /// `StepArgs::abs_steps_with_aliases` applies to `--only`, so a
+    /// `[[steps]]` list and a `--only` flag can use the same macros
+    /// interchangeably. Run once right after deserialization so
+    /// `update_packages`/`update_params` only ever see resolved steps.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let mut config = Config::new();
+    /// config.step_tokens = vec!["preprocess".into(), "minimap2".into()];
+    /// config.aliases.insert("preprocess".into(), vec!["ccs".into(), "lima".into()]);
+    ///
+    /// config.expand_step_tokens().unwrap();
+    /// assert_eq!(config.steps().len(), 3);
+    /// ```
+    fn expand_step_tokens(&mut self) -> Result<(), String> {
+        let mut steps = Vec::new();
+
+        for token in &self.step_tokens {
+            let mut seen = HashSet::new();
+            steps.extend(expand_step_token(token, &self.aliases, &mut seen)?);
+        }
+
+        self.steps = steps;
+        Ok(())
+    }
+
     /// Update the parameters in the Config based on the updated steps
     ///
     /// # Example
@@ -391,6 +480,22 @@ impl Config {
         }
     }
 
+    /// Config aliases getter.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the aliases HashMap.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::new();
+    /// let aliases = config.aliases();
+    /// ```
+    pub fn aliases(&self) -> &HashMap<String, Vec<String>> {
+        &self.aliases
+    }
+
     /// Config parameters getter.
     ///
     /// # Returns
@@ -573,8 +678,16 @@ impl Config {
     /// let config = Config::new();
     /// let output = config.get_global_output();
     ///
-    /// assert_eq!(output, PathBuf::from("output_20210901120000"));
+    /// // with `global_output_dir = "out"` and run ID `K3F9`:
+    /// assert_eq!(output, PathBuf::from("out/isopipe_run_K3F9"));
     /// ```
+    ///
+    /// # Note
+    ///
+    /// Named after the run ID set by `set_run_id` (which already checked
+    /// this exact path for a collision before committing to it), rather
+    /// than a timestamp, so two runs launched in the same minute never
+    /// land in the same directory.
     pub fn create_global_output_dir(&self) -> PathBuf {
         let rs = format!(
             "{}/{}_{}",
@@ -584,7 +697,7 @@ impl Config {
                 .to_path_buf()
                 .display(),
             OUTPUT,
-            chrono::Local::now().format("%Y%m%d%H%M")
+            self.get_run_id()
         )
         .into();
 
@@ -640,11 +753,24 @@ impl Config {
                     package = String::from("pbccs");
                 }
 
-                let version = self
-                    .packages
-                    .get(&package)
-                    .expect(format!("ERROR: Package not found -> {}", package).as_str())
-                    .to_string();
+                let version = match self.packages.get(&package) {
+                    Some(version) => version.to_string(),
+                    None => {
+                        let known: Vec<&str> =
+                            self.packages.keys().map(String::as_str).collect();
+
+                        match suggest(&package, &known, suggestion_distance(&package)) {
+                            Some(best) => log::error!(
+                                "ERROR: Package not found -> {}, did you mean '{}'?",
+                                package,
+                                best
+                            ),
+                            None => log::error!("ERROR: Package not found -> {}", package),
+                        }
+
+                        std::process::exit(1);
+                    }
+                };
 
                 format!("{}/{}", package, version)
             }
@@ -712,16 +838,30 @@ impl Config {
     pub fn get_step_custom_fields(&self, step: &PipelineStep, fields: Vec<&str>) -> Vec<String> {
         fields
             .into_iter()
-            .map(|field| {
-                self.get_param(*step, field)
-                    .expect(
-                        format!("ERROR: {} not found for {} in config.toml!", field, step).as_str(),
-                    )
-                    .to_string()
+            .map(|field| match self.get_param(*step, field) {
+                Some(value) => value.to_string(),
+                None => self.diagnose_missing_field(step, field),
             })
             .collect()
     }
 
+    /// Build and print a span-aware diagnostic for a missing field, then
+    /// abort the run. Pointing at the `[params.<step>]` section (or the
+    /// start of the file if the section isn't present at all) along with
+    /// a "did you mean" suggestion makes misconfiguration actionable
+    /// without a raw `.expect()` backtrace.
+    fn diagnose_missing_field(&self, step: &PipelineStep, field: &str) -> ! {
+        let known: Vec<&str> = self
+            .params
+            .get(step)
+            .map(|p| p.values.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+
+        let error = ConfigError::missing_field(&self.raw, &step.to_string(), field, &known);
+        log::error!("{}", error);
+        std::process::exit(1);
+    }
+
     /// Get arguments for a given step.
     ///
     /// # Arguments
@@ -739,13 +879,165 @@ impl Config {
     /// assert_eq!(args, "arg3 arg4");
     /// ```
     pub fn get_step_args(&self, step: &PipelineStep, exclude: Vec<&str>) -> String {
-        let args = self
-            .params()
-            .get(step)
-            .expect("ERROR: ccs not found in config.toml!")
-            .flat(Some(exclude));
+        match self.params().get(step) {
+            Some(params) => params.flat(Some(exclude)),
+            None => {
+                let known_owned: Vec<String> =
+                    self.params.keys().map(|s| s.to_unique_str()).collect();
+                let known: Vec<&str> = known_owned.iter().map(String::as_str).collect();
+                let (line, column, snippet) = locate_section(&self.raw, &step.to_string());
+                let error = ConfigError {
+                    section: step.to_string(),
+                    key: String::from("[params]"),
+                    line,
+                    column,
+                    snippet,
+                    suggestion: suggest(&step.to_string(), &known, suggestion_distance(&step.to_string())),
+                };
 
-        args
+                log::error!("{}", error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Same as [`Self::get_step_args`], but as an argv (see
+    /// [`StepParams::argv`]) instead of a single flattened string, so a
+    /// caller can hand it to [`crate::executor::job::Job::argv`] or
+    /// [`run_argv`] without re-splitting a value that contains whitespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - The step for which to retrieve arguments.
+    /// * `exclude` - A vector of argument names to exclude.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let step = PipelineStep::Ccs;
+    /// let config = Config::default();
+    /// let argv = config.get_step_argv(&step, vec!["arg1", "arg2"]);
+    /// ```
+    pub fn get_step_argv(&self, step: &PipelineStep, exclude: Vec<&str>) -> Vec<OsString> {
+        match self.params().get(step) {
+            Some(params) => params.argv(Some(exclude)),
+            None => {
+                let known_owned: Vec<String> =
+                    self.params.keys().map(|s| s.to_unique_str()).collect();
+                let known: Vec<&str> = known_owned.iter().map(String::as_str).collect();
+                let (line, column, snippet) = locate_section(&self.raw, &step.to_string());
+                let error = ConfigError {
+                    section: step.to_string(),
+                    key: String::from("[params]"),
+                    line,
+                    column,
+                    snippet,
+                    suggestion: suggest(&step.to_string(), &known, suggestion_distance(&step.to_string())),
+                };
+
+                log::error!("{}", error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Validate this config in a single pass instead of discovering
+    /// problems one `.expect()` panic at a time mid-run: every enabled
+    /// step must have a `[params.<step>]` table with `input_dir`/
+    /// `output_dir` and its own required fields (see
+    /// [`PipelineStep::required_custom_fields`]), its resolved package
+    /// must exist in `[packages]`, and `global.global_output_dir`/
+    /// `global.data_prefix` must be set. Every problem found is collected
+    /// into the returned `Vec` instead of aborting at the first, so a
+    /// misconfigured `config.toml` can be fixed in one edit cycle.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the config is usable, or every [`ConfigError`] found,
+    /// each pointing at the offending line/column of the raw source.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::read(PathBuf::from("config.toml")).unwrap();
+    /// if let Err(errors) = config.validate() {
+    ///     for error in &errors {
+    ///         eprintln!("{}", error);
+    ///     }
+    ///     std::process::exit(1);
+    /// }
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        for key in ["global_output_dir", "data_prefix"] {
+            if self.global.get(key).is_none() {
+                let (line, column, snippet) = locate_header(&self.raw, "[global]");
+                errors.push(ConfigError {
+                    section: "global".to_string(),
+                    key: key.to_string(),
+                    line,
+                    column,
+                    snippet,
+                    suggestion: None,
+                });
+            }
+        }
+
+        for step in &self.steps {
+            let section = step.to_string();
+
+            let Some(params) = self.params.get(step) else {
+                let (line, column, snippet) = locate_section(&self.raw, &section);
+                errors.push(ConfigError {
+                    section: section.clone(),
+                    key: "[params]".to_string(),
+                    line,
+                    column,
+                    snippet,
+                    suggestion: None,
+                });
+                continue;
+            };
+
+            let known: Vec<&str> = params.values.keys().map(String::as_str).collect();
+            let mut required = vec![INPUT_DIR, OUTPUT_DIR];
+            required.extend(step.required_custom_fields());
+
+            for field in required {
+                if params.get(field).is_none() {
+                    errors.push(ConfigError::missing_field(&self.raw, &section, field, &known));
+                }
+            }
+
+            if *step != PipelineStep::Minimap && *step != PipelineStep::Custom {
+                let mut package = step.to_str();
+                if package == "ccs" {
+                    package = String::from("pbccs");
+                }
+
+                if self.packages.get(&package).is_none() {
+                    let known_owned: Vec<String> = self.packages.keys().cloned().collect();
+                    let known: Vec<&str> = known_owned.iter().map(String::as_str).collect();
+                    let (line, column, snippet) = locate_header(&self.raw, "[packages]");
+
+                    errors.push(ConfigError {
+                        section: "packages".to_string(),
+                        key: package.clone(),
+                        line,
+                        column,
+                        snippet,
+                        suggestion: suggest(&package, &known, suggestion_distance(&package)),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
     /// Generates a unique random run ID of 4 characters.
@@ -758,26 +1050,36 @@ impl Config {
     /// let run_id = config.get_run_id();
     /// ```
     pub fn set_run_id(&mut self) {
-        let handle = self
-            .metadata
-            .get_mut(RUN_ID)
-            .expect("ERROR: RUN_ID not found in metadata!");
+        let global_output_dir = self
+            .global
+            .get("global_output_dir")
+            .expect("ERROR: output not found!")
+            .to_path_buf();
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .expect("ERROR: Time went backwards")
-            .as_nanos();
+        const MAX_ATTEMPTS: usize = 32;
+
+        let mut id = random_run_id();
+        let mut attempts = 1;
 
-        let mut id = String::with_capacity(RUN_ID_LEN);
+        while global_output_dir.join(format!("{}_{}", OUTPUT, id)).exists() {
+            if attempts >= MAX_ATTEMPTS {
+                log::error!(
+                    "ERROR: could not draw a unique run ID under '{}' after {} attempts!",
+                    global_output_dir.display(),
+                    MAX_ATTEMPTS
+                );
+                std::process::exit(1);
+            }
 
-        // Use simple deterministic mixing to extract characters
-        let mut hash = now;
-        for _ in 0..RUN_ID_LEN {
-            let idx = (hash % (CHARSET.len() as u128)) as usize;
-            id.push(CHARSET[idx] as char);
-            hash /= 7; // Crude entropy mixing
+            id = random_run_id();
+            attempts += 1;
         }
 
+        let handle = self
+            .metadata
+            .get_mut(RUN_ID)
+            .expect("ERROR: RUN_ID not found in metadata!");
+
         *handle = id;
     }
 
@@ -800,6 +1102,45 @@ impl Config {
             .clone()
     }
 
+    /// Reconstruct a previous run's checkpoint state from `output_dir`
+    /// (written by `checkpoint::RunState::save` as each step completed)
+    /// and return the steps from `self.steps()` that haven't completed
+    /// yet, in their original order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no checkpoint exists at `output_dir`, or if
+    /// the checkpoint's recorded config hash no longer matches this
+    /// config's raw source -- resuming against a changed `config.toml`
+    /// could silently skip steps whose inputs or arguments have since
+    /// changed.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::read(PathBuf::from("config.toml"))?;
+    /// let remaining = config.resume(&PathBuf::from("out/isopipe_run_K3F9"))?;
+    /// ```
+    pub fn resume(&self, output_dir: &Path) -> Result<Vec<PipelineStep>, String> {
+        let state = crate::checkpoint::RunState::load(output_dir)?;
+
+        if state.config_hash != crate::checkpoint::hash_config(self) {
+            return Err(format!(
+                "ERROR: config.toml has changed since the run at {} was checkpointed, refusing to resume!",
+                output_dir.display()
+            ));
+        }
+
+        let completed: HashSet<&str> = state.completed.iter().map(String::as_str).collect();
+
+        Ok(self
+            .steps
+            .iter()
+            .filter(|step| !completed.contains(step.to_unique_str().as_str()))
+            .cloned()
+            .collect())
+    }
+
     /// Get the format package/version for a given package.
     ///
     /// # Example
@@ -821,6 +1162,71 @@ impl Config {
                 .expect(&format!("ERROR: {} not found in config.packages!", package))
         )
     }
+
+    /// Get the `.lua` script path configured for [`PipelineStep::Custom`].
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::default();
+    /// let script = config.get_custom_script();
+    /// ```
+    pub fn get_custom_script(&self) -> String {
+        self.metadata
+            .get(CUSTOM_SCRIPT)
+            .expect("ERROR: custom_script not found in config.metadata!")
+            .clone()
+    }
+
+    /// Get this config's `group_pattern` regex.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::default();
+    /// let pattern = config.get_group_pattern();
+    /// ```
+    pub fn get_group_pattern(&self) -> &str {
+        &self.group_pattern
+    }
+
+    /// Whether `core::samtools::merge` should keep unmerged source BAMs
+    /// (relocated into `unmerged/`) instead of deleting them.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::default();
+    /// let keep_sources = config.get_keep_sources();
+    /// ```
+    pub fn get_keep_sources(&self) -> bool {
+        self.keep_sources
+    }
+
+    /// Get this config's `merge_sort_order` ("coordinate" or "name").
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::default();
+    /// let order = config.get_merge_sort_order();
+    /// ```
+    pub fn get_merge_sort_order(&self) -> &str {
+        &self.merge_sort_order
+    }
+
+    /// Whether `core::samtools::merge` should pass `-c`/`-p` to combine
+    /// `@RG`/`@PG` headers.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let config = Config::default();
+    /// let combine = config.get_merge_combine_headers();
+    /// ```
+    pub fn get_merge_combine_headers(&self) -> bool {
+        self.merge_combine_headers
+    }
 }
 
 impl Default for Config {
@@ -845,6 +1251,27 @@ pub enum PipelineStep {
     Minimap,
     Polya,
     LoadGenome,
+    /// `isotools iso-fusion` over each clustering category's
+    /// `corrected.good.bed` (see [`crate::core::isotools::iso_fusion`]),
+    /// aggregated into the `fusions.*.bed` files [`Self::Orf`] reads.
+    Fusion,
+    /// ORF prediction / sequence extraction over [`Self::Fusion`]'s
+    /// `fusions.*.bed` outputs against a `.2bit` genome (see
+    /// [`crate::core::orf::orf`]).
+    Orf,
+    /// A user-supplied `.lua` script run through the embedded Lua host
+    /// API (see [`crate::lua::run_custom_step`]) instead of a built-in
+    /// tool, so a bespoke post-processing stage can be wired in without
+    /// forking the crate.
+    Custom,
+    /// An arbitrary external tool registered via a `[params.<name>]`
+    /// block that isn't one of the built-ins above, as long as it
+    /// declares a `program`/`command` field (see [`deserialize_to_hash`]).
+    /// The `u32` is a stable hash of the table's name (see
+    /// [`PipelineStep::external_id`]), not a handle into any registry:
+    /// the real name and program live in this step's own [`StepParams`],
+    /// fetched the same way as any other parameter.
+    External(u32),
 }
 
 impl PipelineStep {
@@ -865,6 +1292,10 @@ impl PipelineStep {
     ///
     /// assert_eq!(step, Ok(PipelineStep::Ccs));
     /// ```
+    ///
+    /// An unrecognized token gets a "did you mean" suggestion (see
+    /// [`suggest`]) when it's a close typo of a known step, e.g.
+    /// `"clsuter"` suggests `"cluster"`.
     pub fn from_str(s: &str) -> Result<Self, String> {
         match s {
             "ccs" => Ok(Self::Ccs),
@@ -874,10 +1305,147 @@ impl PipelineStep {
             "minimap2" => Ok(Self::Minimap),
             "polya" => Ok(Self::Polya),
             "load-genome" => Ok(Self::LoadGenome),
-            _ => Err(format!("ERROR: Invalid pipeline step: {}", s)),
+            "iso-fusion" => Ok(Self::Fusion),
+            "orf" => Ok(Self::Orf),
+            "custom" => Ok(Self::Custom),
+            _ => {
+                let known = [
+                    "ccs",
+                    "lima",
+                    "refine",
+                    "cluster",
+                    "minimap2",
+                    "polya",
+                    "load-genome",
+                    "iso-fusion",
+                    "orf",
+                    "custom",
+                ];
+
+                match suggest(s, &known, suggestion_distance(s)) {
+                    Some(best) => Err(format!(
+                        "ERROR: Invalid pipeline step: {}, did you mean '{}'?",
+                        s, best
+                    )),
+                    None => Err(format!("ERROR: Invalid pipeline step: {}", s)),
+                }
+            }
+        }
+    }
+
+    /// Resolve `name` against a user-defined `[aliases]` table (e.g.
+    /// `dedup = "cluster"`, `map = "minimap2"`) before falling back to
+    /// the built-in names handled by [`Self::from_str`], so labs can
+    /// standardize their own vocabulary for steps without editing this
+    /// crate.
+    ///
+    /// Mirrors the cycle protection [`expand_step_token`] already applies
+    /// to the `steps = "..."` macro list, but is scoped to a single alias
+    /// standing in for a single canonical step: an alias whose expansion
+    /// has more than one entry (a `preprocess = [0, 1, 2]`-style macro) is
+    /// rejected here rather than silently picking the first step.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The step name or alias to resolve.
+    /// * `aliases` - The `[aliases]` table to resolve `name` against.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let mut aliases = HashMap::new();
+    /// aliases.insert("dedup".to_string(), vec!["cluster".to_string()]);
+    ///
+    /// let step = PipelineStep::from_str_with_aliases("dedup", &aliases);
+    ///
+    /// assert_eq!(step, Ok(PipelineStep::Cluster));
+    /// ```
+    pub fn from_str_with_aliases(
+        name: &str,
+        aliases: &HashMap<String, Vec<String>>,
+    ) -> Result<Self, String> {
+        fn resolve(
+            name: &str,
+            aliases: &HashMap<String, Vec<String>>,
+            seen: &mut HashSet<String>,
+        ) -> Result<Option<PipelineStep>, String> {
+            match aliases.get(name) {
+                Some(expansion) => {
+                    if !seen.insert(name.to_string()) {
+                        return Err(format!(
+                            "ERROR: cyclic step alias detected while resolving '{}'",
+                            name
+                        ));
+                    }
+
+                    if expansion.len() != 1 {
+                        return Err(format!(
+                            "ERROR: alias '{}' must resolve to exactly one step, found {}",
+                            name,
+                            expansion.len()
+                        ));
+                    }
+
+                    resolve(&expansion[0], aliases, seen)
+                }
+                None => Ok(PipelineStep::from_str(name).ok()),
+            }
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(step) = resolve(name, aliases, &mut seen)? {
+            return Ok(step);
+        }
+
+        let known: Vec<&str> = [
+            "ccs",
+            "lima",
+            "refine",
+            "cluster",
+            "minimap2",
+            "polya",
+            "load-genome",
+            "iso-fusion",
+            "orf",
+            "custom",
+        ]
+        .into_iter()
+        .chain(aliases.keys().map(String::as_str))
+        .collect();
+
+        match suggest(name, &known, suggestion_distance(name)) {
+            Some(best) => Err(format!(
+                "ERROR: Invalid pipeline step: {}, did you mean '{}'?",
+                name, best
+            )),
+            None => Err(format!("ERROR: Invalid pipeline step: {}", name)),
         }
     }
 
+    /// Stable id for a [`Self::External`] step, hashed from its
+    /// `[params.<name>]` table name so the same name always resolves to
+    /// the same id across runs (important for manifest keys, which are
+    /// keyed by [`Self::to_unique_str`]) without needing a
+    /// sequential-assignment registry to be threaded around.
+    ///
+    /// Offset well past the built-ins' `1..=10` range so an external id
+    /// can never be mistaken for one of them by [`Self::from_int`].
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let step = PipelineStep::External(PipelineStep::external_id("rnaseqc"));
+    /// ```
+    pub fn external_id(name: &str) -> u32 {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+
+        1_000 + (hasher.finish() % 1_000_000) as u32
+    }
+
     /// Create a PipelineStep enum from an integer.
     ///
     /// # Arguments
@@ -904,7 +1472,15 @@ impl PipelineStep {
             5 => Ok(Self::Minimap),
             6 => Ok(Self::Polya),
             7 => Ok(Self::LoadGenome),
-            _ => Err(format!("ERROR: Invalid pipeline step: {}", i)),
+            8 => Ok(Self::Fusion),
+            9 => Ok(Self::Orf),
+            10 => Ok(Self::Custom),
+            // INFO: any id beyond the built-ins is an `External` step's
+            // stable name hash (see `PipelineStep::external_id`), not an
+            // invalid token -- there's no fixed upper bound to validate
+            // against without a registry this `&self`-free constructor
+            // doesn't have access to.
+            _ => Ok(Self::External(i as u32)),
         }
     }
 
@@ -936,6 +1512,10 @@ impl PipelineStep {
             Self::Minimap => "minimap2".into(),
             Self::Polya => "polya".into(),
             Self::LoadGenome => "load-genome".into(),
+            Self::Fusion => "isotools".into(),
+            Self::Orf => "orf".into(),
+            Self::Custom => "custom".into(),
+            Self::External(id) => format!("external-{}", id),
         }
     }
 
@@ -967,6 +1547,10 @@ impl PipelineStep {
             Self::Minimap => "minimap2".into(),
             Self::Polya => "polya".into(),
             Self::LoadGenome => "load-genome".into(),
+            Self::Fusion => "iso-fusion".into(),
+            Self::Orf => "orf".into(),
+            Self::Custom => "custom".into(),
+            Self::External(id) => format!("external-{}", id),
         }
     }
 
@@ -993,6 +1577,70 @@ impl PipelineStep {
             Self::Minimap => 5,
             Self::Polya => 6,
             Self::LoadGenome => 7,
+            Self::Fusion => 8,
+            Self::Orf => 9,
+            Self::Custom => 10,
+            Self::External(id) => *id as usize,
+        }
+    }
+
+    /// This step's upstream dependencies: the steps whose outputs it
+    /// reads from. Used to build the dependency DAG in
+    /// [`crate::core::run_graph`] instead of assuming the listed order
+    /// is the only valid schedule.
+    ///
+    /// # Returns
+    ///
+    /// The direct upstream steps; `LoadGenome` and `Ccs` have none, since
+    /// both only read from the user-supplied input/assembly.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let step = PipelineStep::Minimap;
+    /// assert_eq!(step.dependencies(), vec![PipelineStep::Cluster, PipelineStep::LoadGenome]);
+    /// ```
+    pub fn dependencies(&self) -> Vec<PipelineStep> {
+        match self {
+            Self::Ccs => vec![],
+            Self::LoadGenome => vec![],
+            Self::Lima => vec![Self::Ccs],
+            Self::Refine => vec![Self::Lima],
+            Self::Cluster => vec![Self::Refine],
+            Self::Minimap => vec![Self::Cluster, Self::LoadGenome],
+            Self::Polya => vec![Self::Minimap],
+            Self::Fusion => vec![Self::Polya],
+            Self::Orf => vec![Self::Fusion, Self::LoadGenome],
+            Self::Custom => vec![],
+            Self::External(_) => vec![],
+        }
+    }
+
+    /// This step's required custom fields beyond `input_dir`/`output_dir`,
+    /// mirroring the field lists each `core::<step>` module already passes
+    /// to [`Config::get_step_custom_fields`]. Used by [`Config::validate`]
+    /// to check a whole config.toml in one pass instead of discovering a
+    /// missing field mid-run via [`Config::diagnose_missing_field`].
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let step = PipelineStep::Minimap;
+    /// assert_eq!(step.required_custom_fields(), &["genome"]);
+    /// ```
+    pub fn required_custom_fields(&self) -> &'static [&'static str] {
+        match self {
+            Self::Ccs => &[CHUNK, REPORT],
+            Self::Lima => &[PRIMERS],
+            Self::Refine => &[PRIMERS],
+            Self::Cluster => &[LOG_FILE],
+            Self::Minimap => &[GENOME],
+            Self::Polya => &[],
+            Self::LoadGenome => &[],
+            Self::Fusion => &[],
+            Self::Orf => &[GENOME],
+            Self::Custom => &[],
+            Self::External(_) => &[],
         }
     }
 
@@ -1130,8 +1778,72 @@ impl StepParams {
             .join(" ")
     }
 
+    /// Emit the same flags as [`Self::flat`], but as an argv: one
+    /// [`OsString`] per flag/value instead of a single `sh -c` string.
+    ///
+    /// # Returns
+    ///
+    /// A flat list of argument tokens, each value kept verbatim (no
+    /// whitespace splitting), safe to hand directly to
+    /// [`std::process::Command::args`] or [`run_argv`].
+    ///
+    /// # Note
+    ///
+    /// Same short-vs-long dash rule as [`Self::flat`]. `SPECIAL_PARAMETER`
+    /// keys are emitted as a single `--key=value` (or `-key=value`) token;
+    /// every other key/value pair is emitted as two separate tokens.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let params = StepParams {
+    ///    values: HashMap::new(),
+    /// };
+    ///
+    /// let argv = params.argv(None);
+    ///
+    /// assert!(argv.is_empty());
+    /// ```
+    pub fn argv(&self, exclude: Option<Vec<&str>>) -> Vec<OsString> {
+        let exclude = exclude
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        self.values
+            .iter()
+            .filter(|(key, _)| !exclude.contains(key.as_str()))
+            .flat_map(|(key, value)| {
+                let value = match value {
+                    ParamValue::Int(i) => i.to_string(),
+                    ParamValue::Float(flt) => flt.to_string(),
+                    ParamValue::Bool(b) => b.to_string(),
+                    ParamValue::Str(s) => s.clone(),
+                };
+
+                let dash = if key.len() > 2 { "--" } else { "-" };
+
+                if SPECIAL_PARAMETER.contains(&key.as_str()) {
+                    vec![OsString::from(format!("{}{}={}", dash, key, value))]
+                } else {
+                    vec![
+                        OsString::from(format!("{}{}", dash, key)),
+                        OsString::from(value),
+                    ]
+                }
+            })
+            .collect()
+    }
+
     /// Get a parameter value from a StepParams struct.
     ///
+    /// This is also how callers probe optional fallback chains (e.g.
+    /// `ParallelExecutor::execute`'s `memory`/`num-threads` -> `t` ->
+    /// global-default lookups), so a miss here is routine, not a config
+    /// mistake -- it does not warn. The "did you mean" diagnostic for an
+    /// actually-missing *required* field lives in [`ConfigError::missing_field`],
+    /// on the `Config::validate`/`diagnose_missing_field` path instead.
+    ///
     /// # Arguments
     ///
     /// * `key` - A string containing the parameter key.
@@ -1298,31 +2010,225 @@ impl std::fmt::Display for ParamValue {
     }
 }
 
-/// Deserialize a Vec of PipelineStep enums.
+/// A span-aware config diagnostic, pointing at the section of
+/// `config.toml` responsible for a missing or misspelled key.
 ///
-/// # Arguments
+/// # Example
 ///
-/// * `deserializer` - A serde Deserializer.
+/// ``` rust, no_run
+/// let error = ConfigError::missing_field(&config.raw, "ccs", "min-rq", &["chunk", "report-file"]);
+/// eprintln!("{}", error);
+/// ```
+#[derive(Debug)]
+pub struct ConfigError {
+    pub section: String,
+    pub key: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub snippet: Option<String>,
+    pub suggestion: Option<String>,
+}
+
+impl ConfigError {
+    /// Build a diagnostic for a required field missing from the
+    /// `[params.<section>]` table, with a "did you mean" suggestion
+    /// computed via Levenshtein distance against the keys that *are*
+    /// present for that section.
+    pub fn missing_field(raw: &str, section: &str, key: &str, known: &[&str]) -> Self {
+        let (line, column, snippet) = locate_section(raw, section);
+        let suggestion = suggest(key, known, suggestion_distance(key));
+
+        Self {
+            section: section.to_string(),
+            key: key.to_string(),
+            line,
+            column,
+            snippet,
+            suggestion,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // INFO: `global` and `packages` are top-level tables, not `[params.*]` ones.
+        let table = match self.section.as_str() {
+            "global" | "packages" => format!("[{}]", self.section),
+            _ => format!("[params.{}]", self.section),
+        };
+        writeln!(
+            f,
+            "ERROR: missing required key `{}` in {}",
+            self.key, table
+        )?;
+
+        if let (Some(line), Some(column), Some(snippet)) =
+            (self.line, self.column, &self.snippet)
+        {
+            writeln!(f, "  --> config.toml:{}:{}", line, column)?;
+            writeln!(f, "   | {}", snippet)?;
+            writeln!(f, "   | {}^-- section starts here", " ".repeat(column.saturating_sub(1)))?;
+        }
+
+        if let Some(suggestion) = &self.suggestion {
+            writeln!(f, "  = did you mean `{}`?", suggestion)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Locate the `[params.<section>]` header inside the raw TOML source,
+/// returning its 1-indexed line/column and the source line itself.
+fn locate_section(raw: &str, section: &str) -> (Option<usize>, Option<usize>, Option<String>) {
+    locate_header(raw, &format!("[params.{}]", section))
+}
+
+/// Locate a literal TOML table `header` (e.g. `[global]`, `[packages]`)
+/// inside the raw source, returning its 1-indexed line/column and the
+/// source line itself, or `(None, None, None)` if the table isn't present
+/// at all.
+fn locate_header(raw: &str, header: &str) -> (Option<usize>, Option<usize>, Option<String>) {
+    for (idx, line) in raw.lines().enumerate() {
+        if let Some(column) = line.find(header) {
+            return (Some(idx + 1), Some(column + 1), Some(line.to_string()));
+        }
+    }
+
+    (None, None, None)
+}
+
+/// Compute the Levenshtein edit distance between two strings using the
+/// standard two-row dynamic-programming recurrence.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (curr[j] + 1).min(prev[j + 1] + 1).min(prev[j] + cost);
+        }
+
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Find the closest match to `input` among `candidates`, returning it
+/// only if its edit distance is within `max_distance`.
 ///
-/// # Returns
+/// # Example
 ///
-/// A Result containing a Vec of PipelineStep enums or an error.
+/// ``` rust, no_run
+/// let suggestion = suggest("clsuter", &["ccs", "cluster", "polya"], 3);
+/// assert_eq!(suggestion, Some("cluster".to_string()));
+/// ```
+pub fn suggest(input: &str, candidates: &[&str], max_distance: usize) -> Option<String> {
+    candidates
+        .iter()
+        .map(|candidate| (*candidate, levenshtein(input, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate.to_string())
+}
+
+/// Default `max_distance` for [`suggest`]: a third of `input`'s length,
+/// never below 2, instead of a flat cutoff, so a long token tolerates
+/// proportionally more typos while a short one is only matched to a
+/// candidate at most 2 edits away.
 ///
 /// # Example
 ///
 /// ``` rust, no_run
-/// let steps = deserialize_steps("ccs,lima");
+/// assert_eq!(suggestion_distance("rq"), 2);
+/// assert_eq!(suggestion_distance("minimpa2"), 2);
+/// ```
+pub fn suggestion_distance(input: &str) -> usize {
+    std::cmp::max(2, input.len() / 3)
+}
+
+/// Deserialize the raw `steps = "ccs,lima,..."` token list as strings,
+/// without resolving them to `PipelineStep`s yet. `[aliases]` may not be
+/// deserialized yet at this point in the struct, so alias/step
+/// resolution happens afterwards, once the whole `Config` -- and
+/// therefore `aliases` -- is available (see `Config::expand_step_tokens`).
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let tokens = deserialize_step_tokens("ccs,preprocess");
 /// ```
 ///
 /// ``` toml
-/// steps = "ccs,lima"
+/// steps = "ccs,preprocess"
 /// ```
-fn deserialize_steps<'de, D>(deserializer: D) -> Result<Vec<PipelineStep>, D::Error>
+fn deserialize_step_tokens<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     let s: Option<String> = Option::deserialize(deserializer)?;
-    Ok(s.map_or(vec![], |_| vec![]))
+
+    Ok(s.map(|s| {
+        s.split(',')
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .collect()
+    })
+    .unwrap_or_default())
+}
+
+/// Expand a single `steps = "..."` token into its resolved
+/// `PipelineStep` sequence: a plain step name/number resolves directly,
+/// while a `[aliases]` macro splices in its expansion, recursively
+/// resolving any alias it references in turn. Cycles (an alias that
+/// transitively references itself) are rejected rather than looping
+/// forever.
+///
+/// # Arguments
+///
+/// * `token` - The raw token to resolve.
+/// * `aliases` - The `[aliases]` table to resolve macro tokens against.
+/// * `seen` - Aliases already expanded on the current path, used for
+///   cycle detection; start with an empty set.
+fn expand_step_token(
+    token: &str,
+    aliases: &HashMap<String, Vec<String>>,
+    seen: &mut HashSet<String>,
+) -> Result<Vec<PipelineStep>, String> {
+    if let Ok(step) = PipelineStep::from_str(token) {
+        return Ok(vec![step]);
+    }
+
+    if let Ok(i) = token.parse::<usize>() {
+        if let Ok(step) = PipelineStep::from_int(i) {
+            return Ok(vec![step]);
+        }
+    }
+
+    match aliases.get(token) {
+        Some(expansion) => {
+            if !seen.insert(token.to_string()) {
+                return Err(format!("ERROR: alias cycle detected at '{}'", token));
+            }
+
+            let mut steps = Vec::new();
+            for nested in expansion {
+                steps.extend(expand_step_token(nested, aliases, seen)?);
+            }
+
+            Ok(steps)
+        }
+        None => Err(format!("ERROR: invalid step or alias '{}'", token)),
+    }
 }
 
 /// Deserialize a HashMap of PipelineStep enums and StepParams.
@@ -1335,6 +2241,14 @@ where
 ///
 /// A Result containing a HashMap of PipelineStep enums and StepParams or an error.
 ///
+/// Deserialize the `[params.*]` tables into a step -> [`StepParams`] map.
+///
+/// A table name that isn't one of [`PipelineStep::from_str`]'s built-ins
+/// is still accepted as a [`PipelineStep::External`] step, as long as its
+/// table declares a `program` or `command` field -- this is how a config
+/// registers an arbitrary external tool as a first-class pipeline step
+/// without forking the crate. A table matching neither keeps
+/// `from_str`'s original error (including its "did you mean" suggestion).
 fn deserialize_to_hash<'de, D>(
     deserializer: D,
 ) -> Result<HashMap<PipelineStep, StepParams>, D::Error>
@@ -1344,11 +2258,63 @@ where
     let raw: HashMap<String, StepParams> = HashMap::deserialize(deserializer)?;
 
     raw.into_iter()
-        .map(|(key, value)| PipelineStep::from_str(&key).map(|step| (step, value)))
+        .map(|(key, value)| match PipelineStep::from_str(&key) {
+            Ok(step) => Ok((step, value)),
+            Err(builtin_err) => {
+                if value.values.contains_key(PROGRAM) || value.values.contains_key(COMMAND) {
+                    Ok((PipelineStep::External(PipelineStep::external_id(&key)), value))
+                } else {
+                    Err(builtin_err)
+                }
+            }
+        })
         .collect::<Result<HashMap<_, _>, _>>()
         .map_err(serde::de::Error::custom)
 }
 
+/// Deserialize a `[aliases]` table where each entry is either a single
+/// step token (`cluster_phase = "4"`) or a list of step tokens
+/// (`preprocess = [0, 1, 2]`), normalizing both into a `Vec<String>`.
+///
+/// # Arguments
+///
+/// * `deserializer` - A serde Deserializer.
+///
+/// # Returns
+///
+/// A Result containing a HashMap of alias name to step tokens or an error.
+fn deserialize_aliases<'de, D>(deserializer: D) -> Result<HashMap<String, Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AliasValue {
+        Single(toml::Value),
+        Many(Vec<toml::Value>),
+    }
+
+    fn to_string(value: &toml::Value) -> String {
+        match value {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    let raw: HashMap<String, AliasValue> = HashMap::deserialize(deserializer)?;
+
+    Ok(raw
+        .into_iter()
+        .map(|(key, value)| {
+            let expansion = match value {
+                AliasValue::Single(v) => vec![to_string(&v)],
+                AliasValue::Many(vs) => vs.iter().map(to_string).collect(),
+            };
+            (key, expansion)
+        })
+        .collect())
+}
+
 /// Run a command and return the exit status.
 ///
 /// # Arguments
@@ -1515,3 +2481,61 @@ pub fn shell(cmd: String, log_msg: &str, tool: &str) {
         std::process::exit(1);
     }
 }
+
+/// Run `program` directly via [`Command::args`], with no `sh -c` in
+/// between: each entry in `args` reaches the child process verbatim, so a
+/// parameter value containing spaces, quotes, or shell metacharacters
+/// (see [`StepParams::argv`]) can never be mis-split or injected.
+///
+/// Unlike [`shell`], this does not log on success/failure itself; the
+/// caller decides what to do with the returned status.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// use std::ffi::OsString;
+///
+/// let status = run_argv("echo", &[OsString::from("hi")]);
+/// assert!(status.success());
+/// ```
+pub fn run_argv(program: &str, args: &[OsString]) -> ExitStatus {
+    Command::new(program)
+        .args(args)
+        .status()
+        .expect("ERROR: Failed to execute process")
+}
+
+/// Draw a fresh `RUN_ID_LEN`-character run ID from `CHARSET` via a
+/// seeded RNG, replacing the old `nanos / 7` "crude entropy mixing".
+/// The seed mixes the current time with the process ID and (best-effort)
+/// hostname, so distributed submissions against a shared output
+/// filesystem draw from disjoint sequences even under clock skew.
+fn random_run_id() -> String {
+    use rand::{Rng, SeedableRng};
+
+    let mut seed_hasher = std::collections::hash_map::DefaultHasher::new();
+    {
+        use std::hash::{Hash, Hasher};
+
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("ERROR: Time went backwards")
+            .as_nanos()
+            .hash(&mut seed_hasher);
+        std::process::id().hash(&mut seed_hasher);
+        hostname().hash(&mut seed_hasher);
+    }
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(std::hash::Hasher::finish(&seed_hasher));
+
+    (0..RUN_ID_LEN)
+        .map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char)
+        .collect()
+}
+
+/// Best-effort hostname used to seed [`random_run_id`]; an empty string
+/// if it can't be read, since the hostname is only there to widen the
+/// entropy pool, not to be relied on.
+fn hostname() -> String {
+    std::env::var("HOSTNAME").unwrap_or_default()
+}
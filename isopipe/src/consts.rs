@@ -21,6 +21,14 @@ pub const TOGA: &str = "toga";
 pub const ASSEMBLY: &str = "assembly";
 pub const NUM_THREADS: &str = "num-threads";
 pub const NUM_CORES: &str = "num-cores";
+pub const CUSTOM_SCRIPT: &str = "custom_script";
+pub const PROGRAM: &str = "program";
+pub const COMMAND: &str = "command";
+pub const PER_ID: &str = "perID";
+pub const CLIP3: &str = "clip3";
+pub const POLYA_READ_SUFFIX: &str = "polyAReadSuffix";
+pub const COMPRESS: &str = "compress";
+pub const SUBSET: &str = "subset";
 
 // project-wide pub const | names
 pub const ISOPIPE: &str = "isopipe";
@@ -59,6 +67,19 @@ pub const SHORT_QUEUE: &str = "short_queue";
 pub const DEFAULT_MEMORY: &str = "default_memory";
 pub const DEFAULT_THREADS: &str = "default_threads";
 
+// retry policy consts
+pub const MAX_RETRIES: &str = "max_retries";
+pub const RETRY_BACKOFF_MS: &str = "retry_backoff_ms";
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 2000;
+pub const MEMORY_ESCALATION_FACTOR: f64 = 2.0;
+
+// directory scan consts
+pub const MAX_DEPTH: &str = "max_depth";
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+pub const DEFAULT_GROUP_PATTERN: &str = r"^[^.]+\.(?P<group>[^.]+)\.";
+pub const UNMERGED_DIR: &str = "unmerged";
+
 // miscellaneous constants
 pub const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
 pub const RUN_ID_LEN: usize = 4;
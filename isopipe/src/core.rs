@@ -1,4 +1,8 @@
-use crate::{config::*, executor::manager::ParallelExecutor};
+use crate::{
+    config::*, consts::*, executor::job::Job, executor::manager::ParallelExecutor,
+    manifest::Manifest,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 
 pub mod ccs;
@@ -6,25 +10,212 @@ pub mod isoseq;
 pub mod isotools;
 pub mod lima;
 pub mod minimap;
+pub mod orf;
 pub mod pbindex;
 pub mod polya;
+pub mod polya_sam;
 pub mod samtools;
+pub mod verify;
 
 pub fn run(
     config: Config,
     global_output_dir: PathBuf,
     mut executor: ParallelExecutor,
+    force: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     log::info!("SUCCESS: All dependecies are loaded, starting pipeline...");
     // log::info!("INFO: Running with the following config: {:#?}", config);
 
-    config.steps().iter().for_each(|step| {
-        run_step(step, &config, &global_output_dir, &mut executor);
-    });
+    run_steps(
+        config.steps().clone(),
+        &config,
+        &global_output_dir,
+        &mut executor,
+        force,
+    );
 
     Ok(())
 }
 
+/// Schedule `steps` as a dependency DAG (see [`run_graph`]), skipping any
+/// that are fresh according to the `.isopipe/manifest.toml` checkpoint
+/// manifest unless `force` is set.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// run_steps(config.steps().clone(), &config, &global_output_dir, &mut executor, false);
+/// ```
+pub fn run_steps(
+    steps: Vec<PipelineStep>,
+    config: &Config,
+    global_output_dir: &PathBuf,
+    executor: &mut ParallelExecutor,
+    force: bool,
+) {
+    let mut manifest = Manifest::load(global_output_dir);
+    let mut state = crate::checkpoint::RunState::load(global_output_dir)
+        .unwrap_or_else(|_| crate::checkpoint::RunState::new(config));
+
+    let mut digests = HashMap::new();
+    for step in &steps {
+        let (input_dir, _) = config.get_step_dirs(step, global_output_dir);
+        digests.insert(
+            step.to_unique_str(),
+            crate::manifest::compute_digest(step, config, &input_dir),
+        );
+    }
+
+    let runnable = crate::manifest::filter_fresh_steps(steps, &manifest, &digests, force);
+
+    run_graph(
+        runnable,
+        config,
+        global_output_dir,
+        executor,
+        &mut manifest,
+        &mut state,
+        &digests,
+    );
+}
+
+/// Schedule `steps` as a dependency DAG (see [`PipelineStep::dependencies`])
+/// via Kahn's algorithm instead of assuming list order is the only valid
+/// schedule: every step whose dependencies have already completed is
+/// "ready", and a whole ready wave is handed to the executor at once, each
+/// step on its own thread with its own [`ParallelExecutor`] clone so
+/// sibling branches (e.g. independent root steps) run concurrently. The
+/// next wave is only computed once every step in the current one has
+/// returned.
+///
+/// Steps whose dependency isn't present in `steps` are treated as already
+/// satisfied, so a partial graph (e.g. `run-step --only`) schedules
+/// correctly without requiring its ancestors to be listed too.
+///
+/// # Arguments
+///
+/// * `manifest`/`digests` - Used to record each step's checkpoint as soon
+///   as its wave completes, same as the old linear scheduler did.
+/// * `state` - The whole-run [`crate::checkpoint::RunState`], updated and
+///   archived to disk alongside `manifest` so a killed run can be picked
+///   back up with `Config::resume` instead of restarting from `ccs`.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// run_graph(runnable, &config, &global_output_dir, &mut executor, &mut manifest, &mut state, &digests);
+/// ```
+fn run_graph(
+    steps: Vec<PipelineStep>,
+    config: &Config,
+    global_output_dir: &PathBuf,
+    executor: &mut ParallelExecutor,
+    manifest: &mut Manifest,
+    state: &mut crate::checkpoint::RunState,
+    digests: &HashMap<String, String>,
+) {
+    let present: HashSet<PipelineStep> = steps.iter().cloned().collect();
+
+    let mut indegree: HashMap<PipelineStep, usize> = HashMap::new();
+    let mut dependents: HashMap<PipelineStep, Vec<PipelineStep>> = HashMap::new();
+
+    for step in &steps {
+        let deps: Vec<PipelineStep> = step
+            .dependencies()
+            .into_iter()
+            .filter(|dep| present.contains(dep))
+            .collect();
+
+        indegree.insert(*step, deps.len());
+        for dep in deps {
+            dependents.entry(dep).or_default().push(*step);
+        }
+    }
+
+    let mut remaining = indegree.clone();
+    let mut ready: VecDeque<PipelineStep> = indegree
+        .iter()
+        .filter(|(_, &degree)| degree == 0)
+        .map(|(step, _)| *step)
+        .collect();
+
+    let mut scheduled = HashSet::new();
+
+    while !ready.is_empty() {
+        let wave: Vec<PipelineStep> = ready.drain(..).collect();
+
+        log::info!(
+            "INFO: scheduling {} step/s concurrently: {:?}",
+            wave.len(),
+            wave.iter().map(|s| s.to_unique_str()).collect::<Vec<_>>()
+        );
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = wave
+                .iter()
+                .map(|step| {
+                    let step = *step;
+                    let mut local_executor = executor.clone_for_step();
+
+                    scope.spawn(move || {
+                        run_step(&step, config, global_output_dir, &mut local_executor);
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                handle
+                    .join()
+                    .expect("ERROR: a pipeline step thread panicked");
+            }
+        });
+
+        for step in &wave {
+            scheduled.insert(*step);
+
+            // WARN: only record the checkpoint after the step's jobs have
+            // actually been dispatched, so a crash mid-step is a miss next time.
+            let digest = digests
+                .get(&step.to_unique_str())
+                .cloned()
+                .unwrap_or_default();
+            let (_, step_output_dir) = config.get_step_dirs(step, global_output_dir);
+
+            manifest.record(step, digest, vec![step_output_dir.clone()]);
+            state.record(step, &step_output_dir);
+
+            if let Some(deps) = dependents.get(step) {
+                for dependent in deps {
+                    let entry = remaining
+                        .get_mut(dependent)
+                        .expect("ERROR: dependent step missing from in-degree map");
+                    *entry -= 1;
+                    if *entry == 0 {
+                        ready.push_back(*dependent);
+                    }
+                }
+            }
+        }
+
+        manifest.save(global_output_dir);
+        state.save(global_output_dir);
+    }
+
+    if scheduled.len() != steps.len() {
+        let stuck: Vec<String> = steps
+            .iter()
+            .filter(|step| !scheduled.contains(step))
+            .map(|step| step.to_unique_str())
+            .collect();
+
+        log::error!(
+            "ERROR: dependency cycle detected among steps, never became ready: {:?}",
+            stuck
+        );
+        std::process::exit(1);
+    }
+}
+
 pub fn run_step(
     step: &PipelineStep,
     config: &Config,
@@ -43,7 +234,7 @@ pub fn run_step(
             log::info!("INFO [STEP 2]: Pre-processing for lima started...");
             let input_dir = &global_output_dir.join(input_dir);
 
-            samtools::merge(input_dir, executor, config, prefix);
+            samtools::merge(step, input_dir, executor, config);
             lima::lima(step, config, input_dir, &step_output_dir)
         }
         PipelineStep::Refine => {
@@ -82,8 +273,33 @@ pub fn run_step(
                 &step_output_dir,
             )
         }
+        PipelineStep::LoadGenome => {
+            log::info!("INFO [STEP 7]: Pre-processing for load-genome started...");
+
+            // INFO: no built-in tool prepares a genome assembly, so this
+            // dispatches a user-configured `program`/`command` exactly
+            // like `External` does, rather than inventing one.
+            let program = config
+                .get_param(*step, PROGRAM)
+                .or_else(|| config.get_param(*step, COMMAND))
+                .map(ParamValue::to_string)
+                .unwrap_or_else(|| {
+                    log::error!(
+                        "ERROR: load-genome step is missing both `program` and `command` in config.toml!",
+                    );
+                    std::process::exit(1);
+                });
+
+            let args = config.get_step_argv(step, vec![PROGRAM, COMMAND, INPUT_DIR, OUTPUT_DIR]);
+
+            vec![Job::new()
+                .program(&program)
+                .input(global_output_dir.join(&input_dir))
+                .output(step_output_dir.clone())
+                .argv(args)]
+        }
         PipelineStep::Fusion => {
-            log::info!("INFO [STEP 7]: Pre-processing for iso-fusion started...");
+            log::info!("INFO [STEP 8]: Pre-processing for iso-fusion started...");
             isotools::iso_fusion(
                 step,
                 config,
@@ -92,10 +308,70 @@ pub fn run_step(
             )
         }
         PipelineStep::Orf => {
-            todo!()
+            log::info!("INFO [STEP 9]: Pre-processing for orf started...");
+            orf::orf(
+                step,
+                config,
+                &global_output_dir.join(input_dir),
+                &step_output_dir,
+            )
+        }
+        PipelineStep::External(_) => {
+            log::info!("INFO: Pre-processing for external step {} started...", step);
+
+            let program = config
+                .get_param(*step, PROGRAM)
+                .or_else(|| config.get_param(*step, COMMAND))
+                .map(ParamValue::to_string)
+                .unwrap_or_else(|| {
+                    log::error!(
+                        "ERROR: external step {} is missing both `program` and `command` in config.toml!",
+                        step
+                    );
+                    std::process::exit(1);
+                });
+
+            let args = config.get_step_argv(
+                step,
+                vec![PROGRAM, COMMAND, INPUT_DIR, OUTPUT_DIR],
+            );
+
+            vec![Job::new()
+                .program(&program)
+                .input(global_output_dir.join(&input_dir))
+                .output(step_output_dir.clone())
+                .argv(args)]
+        }
+        PipelineStep::Custom => {
+            log::info!("INFO [STEP 10]: Pre-processing for custom step started...");
+            let script = config.get_custom_script();
+            crate::lua::run_custom_step(
+                config,
+                &global_output_dir.join(input_dir),
+                &step_output_dir,
+                &PathBuf::from(script),
+            )
         }
     };
 
+    // INFO: freshness is already decided once, up front, by `run_steps`'
+    // `crate::manifest::filter_fresh_steps` call -- a step only reaches
+    // here at all once the manifest considers it dirty (or `--force` was
+    // passed), so there is no second freshness check to make here.
+    let errors = crate::executor::job::validate_jobs(&jobs);
+    if !errors.is_empty() {
+        for error in &errors {
+            log::error!("{}", error);
+        }
+        log::error!(
+            "ERROR: {} of {} jobs for {} failed validation, aborting before dispatch!",
+            errors.len(),
+            jobs.len(),
+            step
+        );
+        std::process::exit(1);
+    }
+
     executor
         .add_jobs(jobs)
         .execute(config, step, global_output_dir.clone());
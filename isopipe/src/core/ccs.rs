@@ -35,35 +35,77 @@ pub fn ccs(
     step_output_dir: &PathBuf,
     prefix: String,
     executor: &mut ParallelExecutor,
+) -> Vec<Job> {
+    let bams = std::fs::read_dir(input_dir)
+        .expect("Failed to read assets directory")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.eq_ignore_ascii_case(BAM))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+
+    ccs_for_bams(step, config, &bams, step_output_dir, prefix, executor)
+}
+
+/// Build CCS jobs for exactly `bams`, instead of rescanning `input_dir`.
+///
+/// Used by [`ccs`] for the normal full-directory run, and by
+/// [`crate::watch::run_new_inputs`] to generate jobs for only the BAM
+/// files that have newly arrived since the watcher started.
+///
+/// # Returns
+/// A vector of jobs to be executed.
+pub fn ccs_for_bams(
+    step: &PipelineStep,
+    config: &Config,
+    bams: &[PathBuf],
+    step_output_dir: &PathBuf,
+    prefix: String,
+    executor: &mut ParallelExecutor,
 ) -> Vec<Job> {
     let mut jobs = Vec::new();
-    let mut require_pbi = Vec::new();
 
     let fields = config.get_step_custom_fields(step, vec![CHUNK, REPORT]);
-    let args = config.get_step_args(
+    let args = config.get_step_argv(
         step,
         vec![
             INPUT_DIR, PREFIX, OUTPUT_DIR, CHUNK, MEMORY, TIME, REPORT, NUM_CORES,
         ],
     );
 
-    for (_, entry) in std::fs::read_dir(input_dir)
-        .expect("Failed to read assets directory")
-        .flatten()
-        .filter(|entry| {
-            entry
-                .path()
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| ext.eq_ignore_ascii_case(BAM))
-                .unwrap_or(false)
+    // INFO: pbindex is dispatched through its own `and_send` call (not the
+    // wave/topo_sort path `execute()` runs these CCS jobs through later),
+    // and blocks until it returns -- so by generating every missing .pbi
+    // here, up front, the CCS jobs below never need to express this as a
+    // DAG dependency that `execute()` would have no way to see.
+    let require_pbi: Vec<PathBuf> = bams
+        .iter()
+        .filter(|bam| {
+            let mut pbi = (*bam).clone();
+            pbi.set_extension("bam.pbi");
+            !pbi.exists()
         })
-        .enumerate()
-    {
+        .cloned()
+        .collect();
+
+    if !require_pbi.is_empty() {
+        log::warn!(
+            "WARN: {} BAM file/s missing a .pbi, generating index/es before chunking...",
+            require_pbi.len()
+        );
+
+        pbindex::pbindex(require_pbi, config, executor, step_output_dir);
+    }
+
+    for bam in bams {
         let chunk_size = fields[0]
             .parse::<usize>()
             .expect("ERROR: Failed to parse chunk size");
-        let bam = entry.path();
+        let bam = bam.clone();
 
         for chunk_idx in 0..chunk_size {
             let chunk_idx = chunk_idx + 1;
@@ -90,6 +132,8 @@ pub fn ccs(
 
             let job = Job::new()
                 .task(PipelineStep::Ccs)
+                .input(bam.clone())
+                .output(out_bam.clone())
                 .arg(bam.to_str().expect("ERROR: failed to convert path to str"))
                 .arg(
                     out_bam
@@ -97,27 +141,11 @@ pub fn ccs(
                         .expect("ERROR: failed to convert path to str"),
                 )
                 .arg(&chunks)
-                .arg(&args)
+                .argv(args.clone())
                 .arg(&report);
 
             jobs.push(job)
         }
-
-        // WARN: need to check if bam has a .pbi file -> if not, run pbindex
-        let mut pbi = bam.clone();
-        pbi.set_extension("bam.pbi");
-        if !pbi.exists() {
-            log::warn!(
-                "WARN: pbi file not found for {}, generating index...",
-                bam.display()
-            );
-
-            require_pbi.push(bam.clone());
-        }
-    }
-
-    if !require_pbi.is_empty() {
-        pbindex::pbindex(require_pbi, &config, executor, step_output_dir);
     }
 
     log::info!("INFO [STEP 1]: Pre-processing completed -> Running...");
@@ -31,7 +31,7 @@ pub fn refine(
 ) -> Vec<Job> {
     let mut jobs = Vec::new();
 
-    let args = config.get_step_args(step, vec![INPUT_DIR, OUTPUT_DIR, MEMORY, TIME, PRIMERS]);
+    let args = config.get_step_argv(step, vec![INPUT_DIR, OUTPUT_DIR, MEMORY, TIME, PRIMERS]);
     let fields = config.get_step_custom_fields(step, vec![PRIMERS]);
 
     // INFO: format of files: {prefix}.{name}.ccs.merged.fl.{primers}.bam
@@ -64,7 +64,7 @@ pub fn refine(
                     .to_str()
                     .expect("ERROR: failed to convert path to str"),
             )
-            .arg(&args);
+            .argv(args.clone());
 
         jobs.push(job)
     }
@@ -111,15 +111,16 @@ pub fn cluster(
         CLUSTER,
     );
 
-    let args = config.get_step_args(step, vec![INPUT_DIR, OUTPUT_DIR, MEMORY, TIME, LOG_FILE]);
+    let args = config.get_step_argv(step, vec![INPUT_DIR, OUTPUT_DIR, MEMORY, TIME, LOG_FILE]);
     let out_bam = format!("{}/{}", step_output_dir.display(), CLUSTERED_BAM);
     let fields = config.get_step_custom_fields(step, vec![LOG_FILE]);
 
     let jobs = vec![Job::new()
         .task(*step)
+        .require_flag("--log-file")
         .arg(&all_fofn)
         .arg(&out_bam)
-        .arg(&args)
+        .argv(args)
         .arg(format!("--log-file {}/{}", &step_output_dir.display(), fields[0]).as_str())];
 
     log::info!("INFO [STEP 4]: Pre-processing completed -> Running...");
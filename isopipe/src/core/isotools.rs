@@ -89,8 +89,12 @@ fn aggregate_fusions(step_output_dir: &PathBuf) -> Vec<Job> {
             } else {
                 format!("{0}/*/*.{1}.bed", step_output_dir.display(), ty)
             };
-            let output = format!("{0}/fusions.{1}.bed", step_output_dir.display(), ty);
-            Job::from(format!("cat {} > {}", pattern, output))
+            let output = step_output_dir.join(format!("fusions.{}.bed", ty));
+
+            // INFO: glob() expands the wildcard in-process and feeds the
+            // matched paths to `cat` as discrete argv entries, instead of
+            // handing the pattern to a shell.
+            Job::new().program("cat").glob(&pattern).redirect_stdout(output)
         })
         .collect()
 }
@@ -30,7 +30,7 @@ pub fn lima(
     let mut jobs = Vec::new();
 
     let fields = config.get_step_custom_fields(step, vec![PRIMERS]);
-    let args = config.get_step_args(
+    let args = config.get_step_argv(
         step,
         vec![INPUT_DIR, PREFIX, OUTPUT_DIR, MEMORY, TIME, PRIMERS],
     );
@@ -57,7 +57,7 @@ pub fn lima(
 
         let job = Job::new()
             .task(*step)
-            .arg(&args)
+            .argv(args.clone())
             .arg(bam.to_str().expect("ERROR: failed to convert path to str"))
             .arg(&fields[0])
             .arg(
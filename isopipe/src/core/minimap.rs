@@ -31,7 +31,7 @@ pub fn minimap2(
 ) -> Vec<Job> {
     let mut jobs = Vec::new();
 
-    let args = config.get_step_args(step, vec![INPUT_DIR, OUTPUT_DIR, MEMORY, TIME, GENOME]);
+    let args = config.get_step_argv(step, vec![INPUT_DIR, OUTPUT_DIR, MEMORY, TIME, GENOME]);
     let genome = get_genome(config, step, step_output_dir);
 
     for category in CLUSTERING_CATEGORIES {
@@ -53,7 +53,7 @@ pub fn minimap2(
 
         let job = Job::new()
             .task(*step)
-            .arg(&args)
+            .argv(args.clone())
             .arg(&format!("-o {}", alignment.display()))
             .arg(&genome)
             .arg(reads.display());
@@ -7,9 +7,12 @@ use crate::{
 use config::{write_objs, OverlapType, Sequence, Strand, SCALE};
 use dashmap::DashSet;
 use iso_polya::utils::get_sequences;
+use noodles_bgzf as bgzf;
 use packbed::{record::Bed6, unpack};
 use rayon::prelude::*;
-use std::path::PathBuf;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub fn orf(
     step: &PipelineStep,
@@ -27,6 +30,32 @@ pub fn orf(
 
     let twobit = PathBuf::from(config.get_step_custom_fields(step, vec![GENOME])[0].clone());
 
+    // INFO: parsed once and shared across every fusion file below, instead
+    // of `extract()` re-reading the whole .2bit per file (see
+    // `extract_with_genome`).
+    let (genome, _) = get_sequences(twobit.clone()).unwrap_or_else(|_| {
+        panic!(
+            "ERROR: could not get sequences from .2bit -> {}",
+            twobit.display()
+        )
+    });
+    let genome = Arc::new(genome);
+
+    // INFO: off by default -- plain, uncompressed `.fa` is still what
+    // `extract()`'s public signature produces, for compatibility.
+    let compress = match config.get_param(*step, COMPRESS) {
+        Some(ParamValue::Bool(b)) => *b,
+        Some(v) => v.to_string().eq_ignore_ascii_case("true"),
+        None => false,
+    };
+
+    // INFO: optional allowlist restricting extraction/ORF prediction to a
+    // user-supplied subset of transcript ids, e.g. a curated locus set or
+    // a handful of fusion candidates (see `load_subset_ids`).
+    let subset = config
+        .get_param(*step, SUBSET)
+        .map(|path| Arc::new(load_subset_ids(&path.to_path_buf())));
+
     // INFO: looping through all fusion outputs
     for file in FUSION_FILES {
         let bed = input_dir.join(file);
@@ -37,7 +66,40 @@ pub fn orf(
         }
 
         let filename = file.replace(".bed", "");
-        let fasta = extract(&bed, &twobit, step_output_dir, filename);
+
+        // INFO: the `--alignments` BED must carry the same transcript ids
+        // as the extracted FASTA, otherwise `orf_tree_pipe.py` sees
+        // records it has no sequence for.
+        let bed = match &subset {
+            Some(ids) => {
+                let filtered = step_output_dir.join(format!("{}.subset.bed", filename));
+                filter_bed_to_subset(&bed, ids, &filtered);
+                filtered
+            }
+            None => bed,
+        };
+
+        let fasta = extract_with_genome(
+            &bed,
+            &genome,
+            step_output_dir,
+            filename,
+            compress,
+            subset.as_deref(),
+        );
+
+        let verification_errors = crate::core::verify::verify_extract(&fasta, &bed);
+        if !verification_errors.is_empty() {
+            for error in &verification_errors {
+                log::error!("{}", error);
+            }
+            log::error!(
+                "ERROR: {} integrity check/s failed for {}, aborting before downstream steps consume it!",
+                verification_errors.len(),
+                fasta.display()
+            );
+            std::process::exit(1);
+        }
 
         let cmd = format!(
             "{} --fasta {} --alignments {} --output_dir {} {}",
@@ -82,26 +144,79 @@ pub fn extract(
     twobit: &PathBuf,
     step_output_dir: &PathBuf,
     filename: String,
+) -> PathBuf {
+    let genome = get_sequences(twobit.clone()).unwrap_or_else(|_| {
+        panic!(
+            "ERROR: could not get sequences from .2bit -> {}",
+            twobit.display()
+        )
+    });
+
+    extract_with_genome(reads, &genome.0, step_output_dir, filename, false, None)
+}
+
+/// Same as [`extract`], but takes an already-parsed `genome` (see
+/// [`orf`]'s single `get_sequences` call) instead of re-reading the
+/// `.2bit` file, so a run with multiple [`crate::consts::FUSION_FILES`]
+/// parses the genome once instead of once per file.
+///
+/// When `compress` is set, the accumulated records are sorted by
+/// transcript id and written as a BGZF-compressed `.fa.gz` with a
+/// companion `.fai`/`.gzi` index instead of a plain `.fa` (see
+/// [`write_bgzf_fasta`]), so downstream ORF prediction can look a
+/// transcript up without decompressing the whole file.
+///
+/// # Arguments
+///
+/// * `reads` - Path to the reads file
+/// * `genome` - Chromosome name -> sequence map, already parsed from a `.2bit`
+/// * `step_output_dir` - Path to the output directory
+/// * `compress` - Write a faidx-indexed `.fa.gz` instead of a plain `.fa`
+/// * `subset` - When set, only transcripts whose id is in this allowlist
+///   are extracted (see [`load_subset_ids`]); a warning is logged once if
+///   any requested id is never seen in `reads`.
+///
+/// # Returns
+///
+/// Path to the written transcript FASTA (compressed or plain).
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let fasta = extract_with_genome(&bed, &genome, &step_output_dir, "free".into(), false, None);
+/// ```
+pub fn extract_with_genome(
+    reads: &PathBuf,
+    genome: &std::collections::HashMap<String, Vec<u8>>,
+    step_output_dir: &PathBuf,
+    filename: String,
+    compress: bool,
+    subset: Option<&DashSet<String>>,
 ) -> PathBuf {
     log::info!(
         "INFO: Extracting mapped read sequences [{}] from .2bit file...",
         reads.display()
     );
 
-    let fasta = step_output_dir.join(format!("{}.{}", filename, TRANSCRIPTS_FA));
-    let accumulator: DashSet<String> = DashSet::new();
+    let accumulator: DashSet<(String, String)> = DashSet::new();
+    let matched: Option<DashSet<String>> = subset.map(|_| DashSet::new());
 
     let bed = unpack::<Bed6, _>(vec![reads.clone()], OverlapType::Exon, false).expect(&format!(
         "ERROR: could not unpack reads -> {}",
         reads.display(),
     ));
-    let (genome, _) = get_sequences(twobit.clone()).expect(&format!(
-        "ERROR: could not get sequences from .2bit -> {}",
-        twobit.display(),
-    ));
 
     bed.par_iter().for_each(|(chr, transcripts)| {
         for tx in transcripts {
+            if let Some(ids) = subset {
+                if !ids.contains(&tx.id) {
+                    continue;
+                }
+                if let Some(matched) = &matched {
+                    matched.insert(tx.id.clone());
+                }
+            }
+
             let seq = match tx.strand {
                 Strand::Forward => Sequence::new(
                     genome
@@ -120,16 +235,159 @@ pub fn extract(
                 .reverse_complement(),
             };
 
-            accumulator.insert(format!(">{}\n{}", tx.id, seq.to_string()));
+            accumulator.insert((tx.id.clone(), seq.to_string()));
         }
     });
 
-    write_objs(
-        &accumulator,
+    if let (Some(ids), Some(matched)) = (subset, &matched) {
+        let missing: Vec<String> = ids
+            .iter()
+            .map(|id| id.clone())
+            .filter(|id| !matched.contains(id))
+            .collect();
+
+        if !missing.is_empty() {
+            log::warn!(
+                "WARNING: {} requested transcript id/s from the subset were never seen in {} -> {:?}",
+                missing.len(),
+                reads.display(),
+                missing
+            );
+        }
+    }
+
+    if compress {
+        let fasta = step_output_dir.join(format!("{}.{}.gz", filename, TRANSCRIPTS_FA));
+        let mut records: Vec<(String, String)> = accumulator.into_iter().collect();
+        records.sort_by(|a, b| a.0.cmp(&b.0));
+
+        write_bgzf_fasta(&records, &fasta)
+    } else {
+        let fasta = step_output_dir.join(format!("{}.{}", filename, TRANSCRIPTS_FA));
+        let plain: DashSet<String> = accumulator
+            .iter()
+            .map(|entry| format!(">{}\n{}", entry.0, entry.1))
+            .collect();
+
+        write_objs(
+            &plain,
+            fasta
+                .to_str()
+                .expect("ERROR: could not convert path to str!"),
+        );
+
         fasta
-            .to_str()
-            .expect("ERROR: could not convert path to str!"),
-    );
+    }
+}
+
+/// Write `records` (already sorted by transcript id) as a BGZF-compressed
+/// FASTA, flushing a block boundary before each record so its recorded
+/// virtual position is also a real compressed-block offset -- exactly
+/// what the companion `.gzi` needs to translate the `.fai`'s uncompressed
+/// offset back into a seekable block, giving random access by transcript
+/// id without decompressing the whole file.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let path = write_bgzf_fasta(&records, &fasta_gz);
+/// ```
+fn write_bgzf_fasta(records: &[(String, String)], fasta_gz: &Path) -> PathBuf {
+    let file = std::fs::File::create(fasta_gz)
+        .unwrap_or_else(|_| panic!("ERROR: could not create {}", fasta_gz.display()));
+    let mut writer = bgzf::Writer::new(file);
+
+    let mut fai_lines = Vec::with_capacity(records.len());
+    let mut gzi_entries = Vec::with_capacity(records.len());
+    let mut offset: u64 = 0;
+
+    for (id, seq) in records {
+        writer
+            .flush()
+            .expect("ERROR: could not flush BGZF writer!");
+
+        // INFO: `virtual_position().uncompressed()` is block-relative (it
+        // resets to ~0 right after the flush above), not the cumulative
+        // uncompressed offset the `.fai` below is keyed on -- pairing it
+        // with `.compressed()` as-is would make every entry after the
+        // first describe the wrong block. Pair the real compressed block
+        // offset with our own running `offset` instead, so `.gzi` and
+        // `.fai` agree on the same uncompressed coordinate space.
+        let block_offset = writer.virtual_position().compressed();
+        gzi_entries.push((block_offset, offset));
+
+        let header = format!(">{}\n", id);
+        writer
+            .write_all(header.as_bytes())
+            .expect("ERROR: could not write FASTA header!");
+        offset += header.len() as u64;
+
+        let seq_offset = offset;
+        let line = format!("{}\n", seq);
+        writer
+            .write_all(line.as_bytes())
+            .expect("ERROR: could not write FASTA sequence!");
+        offset += line.len() as u64;
+
+        fai_lines.push(format!(
+            "{}\t{}\t{}\t{}\t{}",
+            id,
+            seq.len(),
+            seq_offset,
+            seq.len(),
+            seq.len() + 1
+        ));
+    }
+
+    writer
+        .try_finish()
+        .expect("ERROR: could not finalize BGZF FASTA!");
+
+    std::fs::write(
+        format!("{}.fai", fasta_gz.display()),
+        fai_lines.join("\n") + "\n",
+    )
+    .expect("ERROR: could not write .fai index!");
+
+    bgzf::gzi::write(format!("{}.gzi", fasta_gz.display()), &gzi_entries)
+        .expect("ERROR: could not write .gzi index!");
+
+    fasta_gz.to_path_buf()
+}
+
+/// Load a newline-delimited transcript id allowlist (see
+/// [`crate::consts::SUBSET`]) -- one id per line, blank lines ignored.
+fn load_subset_ids(path: &Path) -> DashSet<String> {
+    let contents = std::fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("ERROR: could not read transcript subset -> {}", path.display()));
+
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Filter a BED6 `bed` down to the records whose name field (column 4,
+/// the transcript id) is in `subset`, writing the result to `output` --
+/// keeps the `--alignments` BED passed to `ORF_EXE` consistent with the
+/// subset-restricted FASTA [`extract_with_genome`] emits (see [`orf`]).
+fn filter_bed_to_subset(bed: &PathBuf, subset: &DashSet<String>, output: &PathBuf) {
+    let contents = std::fs::read_to_string(bed)
+        .unwrap_or_else(|_| panic!("ERROR: could not read BED -> {}", bed.display()));
+
+    let filtered: String = contents
+        .lines()
+        .filter(|line| {
+            line.split('\t')
+                .nth(3)
+                .map(|id| subset.contains(id))
+                .unwrap_or(false)
+        })
+        .map(|line| format!("{}\n", line))
+        .collect();
 
-    return fasta;
+    std::fs::write(output, filtered)
+        .unwrap_or_else(|_| panic!("ERROR: could not write {}", output.display()));
 }
@@ -35,9 +35,7 @@ pub fn pbindex(
 
     bams.iter().for_each(|bam| {
         let cmd = format!("pbindex {}", bam.display());
-        let job = Job::from(cmd);
-
-        jobs.push(job);
+        jobs.push(Job::from(cmd));
     });
 
     executor
@@ -1,11 +1,17 @@
 use crate::{
     config::*,
     consts::*,
-    executor::{job::Job, manager::__get_assets_dir},
+    core::polya_sam::{filter_to_bed12, FilterThresholds},
+    executor::job::Job,
 };
 use std::path::PathBuf;
 
-/// Run polya mod [3 steps]
+/// Run polya mod
+///
+/// Filters `all.clustered.aligned.{hq,singletons}.sam` by percent identity
+/// and 3' soft-clip/poly(A) tail, then emits the survivors as BED12 --
+/// entirely in-process via [`crate::core::polya_sam`], so this step no
+/// longer shells out to `filter.pl`/`bedtools`.
 ///
 /// # Arguments
 ///
@@ -16,7 +22,8 @@ use std::path::PathBuf;
 ///
 /// # Returns
 ///
-/// A vector of jobs to run
+/// An empty vector: this step's work all runs in-process, so there is
+/// nothing left for the executor to dispatch.
 ///
 /// # Examples
 ///
@@ -37,82 +44,53 @@ pub fn polya(
     input_dir: &PathBuf,
     output_dir: &PathBuf,
 ) -> Vec<Job> {
-    let mut jobs = Vec::new();
-
-    let args = config.get_step_args(
-        step,
-        vec![INPUT_DIR, OUTPUT_DIR, MEMORY, TIME, TOGA, ASSEMBLY],
-    );
-    let fields = config.get_step_custom_fields(step, vec![TOGA, ASSEMBLY]);
-    let assets = __get_assets_dir();
-
-    let filter = assets.join(FILTER_MINIMAP);
-    let correct = assets.join(CORRECT_MINIMAP);
+    let thresholds = FilterThresholds::from_config(step, config);
 
     for category in CLUSTERING_CATEGORIES {
         if *category == "lq" {
             continue;
         }
 
-        // INFO: format -> all.clustered.aligned.{hq,lq,singletons}.sam
+        // INFO: format -> all.clustered.aligned.{hq,singletons}.sam
         let filename = PathBuf::from(format!("{}.{}.{}", CU_ALN, category, SAM));
         let alignment = input_dir.join(&filename);
 
-        // INFO: will output all.clustered.aligned.{hq,lq,singletons}.{good,bad}.sam
-        // INFO: script.perl {].sam --perID 96 --clip3 50 --polyAReadSuffix 30 --outdir {}/first_pass
-        let first_pass = format!(
-            "{} {} {} --outdir {}",
-            filter.display(),
-            alignment.display(),
-            args,
-            output_dir.join(POLYA_FIRST_PASS).display()
-        );
-
-        // INFO: script.py {toga} {}.good.sam {assembly} {].corrected.sam
-        let corrected_sam = output_dir.join(filename.with_extension(CORR_MINIMAP_SAM));
-        let correct_step = format!(
-            "python3 {} {} {} {} {}",
-            correct.display(),
-            fields
-                .get(0)
-                .expect(&format!("ERROR: Could not find TOGA -> {:?}", fields)),
-            output_dir
-                .join(POLYA_FIRST_PASS)
-                .join(filename.with_extension(POLYA_GOOD_SAM))
-                .display(),
-            fields
-                .get(1)
-                .expect(&format!("ERROR: Could not find assembly -> {:?}", fields)),
-            corrected_sam.display()
-        );
+        if !alignment.exists() {
+            log::warn!(
+                "WARNING: {} does not exist, skipping polya filtering for it!",
+                alignment.display()
+            );
+            continue;
+        }
 
-        // INFO: script.perl {}.corrected.sam --polyAReadSuffix 30 --outdir {}
-        let second_pass = format!(
-            "{} {} -polyAReadSuffix 30 --outdir {}",
-            filter.display(),
-            corrected_sam.display(),
-            output_dir.display()
-        );
+        let bed = output_dir.join(filename.with_extension(CORR_MINIMAP_GOOD_BED));
+        filter_to_bed12(&alignment, &thresholds, &bed);
 
-        let convert = format!(
-            "{} {} -i {} -bed12 > {}",
-            BEDTOOLS,
-            BAMTOBED,
-            output_dir
-                .join(filename.with_extension(CORR_MINIMAP_GOOD_SAM))
-                .display(),
-            output_dir
-                .join(filename.with_extension(CORR_MINIMAP_GOOD_BED))
-                .display()
-        );
+        match config.get_param(*step, GENOME) {
+            Some(genome) => {
+                let twobit = genome.to_path_buf();
+                let verification_errors = crate::core::verify::verify_polya(&bed, &twobit);
 
-        jobs.push(Job::from(format!(
-            "{} && {} && {} && {}",
-            first_pass, correct_step, second_pass, convert
-        )));
+                if !verification_errors.is_empty() {
+                    for error in &verification_errors {
+                        log::error!("{}", error);
+                    }
+                    log::error!(
+                        "ERROR: {} integrity check/s failed for {}, aborting before downstream steps consume it!",
+                        verification_errors.len(),
+                        bed.display()
+                    );
+                    std::process::exit(1);
+                }
+            }
+            None => log::warn!(
+                "WARN: no 'genome' field configured for polya, skipping BED12 contig-bound verification for {}",
+                bed.display()
+            ),
+        }
     }
 
     log::info!("INFO [STEP 6]: Pre-processing completed -> Running...");
 
-    return jobs;
+    Vec::new()
 }
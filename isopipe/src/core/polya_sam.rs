@@ -0,0 +1,288 @@
+use std::path::Path;
+
+use rayon::prelude::*;
+use rust_htslib::bam::record::{Aux, Cigar};
+use rust_htslib::bam::{self, Read as BamRead};
+
+use crate::{config::*, consts::*};
+
+/// Percent identity / 3' soft-clip / poly(A) thresholds, mirroring the
+/// flags `filter.pl` used to be invoked with (`--perID`, `--clip3`,
+/// `--polyAReadSuffix`), now read straight out of `[params.polya]`.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterThresholds {
+    pub per_id: f64,
+    pub clip3: usize,
+    pub polya_suffix: usize,
+}
+
+impl FilterThresholds {
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let thresholds = FilterThresholds::from_config(&step, &config);
+    /// ```
+    pub fn from_config(step: &PipelineStep, config: &Config) -> Self {
+        let per_id = config
+            .get_param(*step, PER_ID)
+            .map(|v| {
+                v.to_string()
+                    .parse()
+                    .expect("ERROR: perID must be numeric")
+            })
+            .unwrap_or(96.0);
+
+        let clip3 = config
+            .get_param(*step, CLIP3)
+            .map(|v| {
+                v.to_string()
+                    .parse()
+                    .expect("ERROR: clip3 must be numeric")
+            })
+            .unwrap_or(50);
+
+        let polya_suffix = config
+            .get_param(*step, POLYA_READ_SUFFIX)
+            .map(|v| {
+                v.to_string()
+                    .parse()
+                    .expect("ERROR: polyAReadSuffix must be numeric")
+            })
+            .unwrap_or(30);
+
+        Self {
+            per_id,
+            clip3,
+            polya_suffix,
+        }
+    }
+}
+
+/// Percent identity of one alignment, from its CIGAR's aligned-base count
+/// and its mismatch count -- the same quantity `filter.pl` used to derive
+/// from the CIGAR/MD string.
+///
+/// Per the SAM spec, `NM` (edit distance) counts mismatches *and*
+/// inserted/deleted bases, not mismatches alone -- so for an indel-bearing
+/// read, using it directly as a mismatch count systematically
+/// underestimates identity (and can drive `aligned - mismatches` negative,
+/// silently reporting 0% instead of the true partial value). The
+/// CIGAR-derived indel base count is subtracted back out of `NM` first to
+/// recover the actual mismatch count.
+fn percent_identity(record: &bam::Record) -> f64 {
+    let mut aligned: i64 = 0;
+    let mut indel: i64 = 0;
+
+    for op in record.cigar().iter() {
+        match op {
+            Cigar::Match(n) | Cigar::Equal(n) | Cigar::Diff(n) => aligned += *n as i64,
+            Cigar::Ins(n) | Cigar::Del(n) => indel += *n as i64,
+            _ => {}
+        }
+    }
+
+    if aligned == 0 {
+        return 0.0;
+    }
+
+    let edit_distance = match record.aux(b"NM") {
+        Ok(Aux::U8(n)) => n as i64,
+        Ok(Aux::U16(n)) => n as i64,
+        Ok(Aux::U32(n)) => n as i64,
+        Ok(Aux::I8(n)) => n as i64,
+        Ok(Aux::I16(n)) => n as i64,
+        Ok(Aux::I32(n)) => n as i64,
+        _ => 0,
+    };
+
+    let mismatches = (edit_distance - indel).max(0);
+
+    (aligned - mismatches).max(0) as f64 / aligned as f64 * 100.0
+}
+
+/// Length of the soft-clip at the read's genomic 3' end, and whether it
+/// sits at the start of `SEQ`. A reverse-strand record's `SEQ` is stored
+/// already reverse-complemented, so its genomic 3' end is the *leading*
+/// clip, not the trailing one.
+fn three_prime_clip(record: &bam::Record) -> (usize, bool) {
+    let cigar = record.cigar();
+    let leading = match cigar.first() {
+        Some(Cigar::SoftClip(n)) => *n as usize,
+        _ => 0,
+    };
+    let trailing = match cigar.last() {
+        Some(Cigar::SoftClip(n)) => *n as usize,
+        _ => 0,
+    };
+
+    if record.is_reverse() {
+        (leading, true)
+    } else {
+        (trailing, false)
+    }
+}
+
+/// Whether the `len` clipped bases at the read's genomic 3' end contain a
+/// run of at least `min_len` consecutive poly(A) bases (poly(T), on the
+/// reverse strand, since `SEQ` is already reverse-complemented there).
+fn has_polya_run(seq: &[u8], at_start: bool, len: usize, min_len: usize) -> bool {
+    if len == 0 || len > seq.len() {
+        return false;
+    }
+
+    let clipped = if at_start {
+        &seq[..len]
+    } else {
+        &seq[seq.len() - len..]
+    };
+    let base = if at_start { b'T' } else { b'A' };
+
+    let mut run = 0;
+    let mut best = 0;
+    for &b in clipped {
+        if b.to_ascii_uppercase() == base {
+            run += 1;
+            best = best.max(run);
+        } else {
+            run = 0;
+        }
+    }
+
+    best >= min_len
+}
+
+/// Whether `record` survives the perID/clip3/poly(A) filter.
+fn passes(record: &bam::Record, thresholds: &FilterThresholds) -> bool {
+    if record.is_unmapped() {
+        return false;
+    }
+
+    if percent_identity(record) < thresholds.per_id {
+        return false;
+    }
+
+    let (clip_len, at_start) = three_prime_clip(record);
+    clip_len <= thresholds.clip3
+        || has_polya_run(
+            &record.seq().as_bytes(),
+            at_start,
+            clip_len,
+            thresholds.polya_suffix,
+        )
+}
+
+/// Walk a CIGAR into BED12 block sizes/starts (relative to the
+/// alignment's start) and its `chrom_end`, treating `N` ops as intron
+/// gaps between blocks -- same semantics as `bedtools bamtobed -bed12`.
+fn cigar_to_blocks(record: &bam::Record) -> (Vec<i64>, Vec<i64>, i64) {
+    let mut block_sizes = Vec::new();
+    let mut block_starts = Vec::new();
+
+    let mut offset: i64 = 0;
+    let mut block_start: i64 = 0;
+    let mut block_len: i64 = 0;
+
+    for op in record.cigar().iter() {
+        match op {
+            Cigar::Match(n) | Cigar::Equal(n) | Cigar::Diff(n) | Cigar::Del(n) => {
+                block_len += *n as i64;
+                offset += *n as i64;
+            }
+            Cigar::RefSkip(n) => {
+                block_sizes.push(block_len);
+                block_starts.push(block_start);
+                offset += *n as i64;
+                block_start = offset;
+                block_len = 0;
+            }
+            _ => {}
+        }
+    }
+
+    if block_len > 0 {
+        block_sizes.push(block_len);
+        block_starts.push(block_start);
+    }
+
+    (block_sizes, block_starts, record.pos() + offset)
+}
+
+fn to_bed12(record: &bam::Record, chrom: &str) -> String {
+    let (block_sizes, block_starts, chrom_end) = cigar_to_blocks(record);
+    let chrom_start = record.pos();
+    let name = String::from_utf8_lossy(record.qname()).into_owned();
+    let strand = if record.is_reverse() { '-' } else { '+' };
+
+    let sizes = block_sizes
+        .iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    let starts = block_starts
+        .iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        "{}\t{}\t{}\t{}\t0\t{}\t{}\t{}\t0\t{}\t{},\t{},",
+        chrom,
+        chrom_start,
+        chrom_end,
+        name,
+        strand,
+        chrom_start,
+        chrom_end,
+        block_sizes.len(),
+        sizes,
+        starts,
+    )
+}
+
+/// Read `sam`, drop reads failing `thresholds` (percent identity and
+/// 3' soft-clip/poly(A) checks), and write the survivors as BED12 to
+/// `bed_out` -- the in-process replacement for `filter.pl` piped into
+/// `bedtools bamtobed -bed12`, running the per-read work under rayon
+/// (same pool [`crate::core::orf::extract`] already uses).
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// filter_to_bed12(&alignment, &thresholds, &bed);
+/// ```
+pub fn filter_to_bed12(sam: &Path, thresholds: &FilterThresholds, bed_out: &Path) {
+    let mut reader = bam::Reader::from_path(sam)
+        .unwrap_or_else(|_| panic!("ERROR: could not open SAM -> {}", sam.display()));
+
+    let tid_names: Vec<String> = reader
+        .header()
+        .target_names()
+        .iter()
+        .map(|name| String::from_utf8_lossy(name).into_owned())
+        .collect();
+
+    let records: Vec<bam::Record> = reader
+        .records()
+        .map(|r| r.expect("ERROR: malformed SAM record!"))
+        .collect();
+
+    let mut lines: Vec<String> = records
+        .par_iter()
+        .filter(|record| passes(record, thresholds))
+        .map(|record| {
+            let chrom = tid_names
+                .get(record.tid() as usize)
+                .cloned()
+                .unwrap_or_default();
+            to_bed12(record, &chrom)
+        })
+        .collect();
+    lines.sort();
+
+    if let Some(parent) = bed_out.parent() {
+        std::fs::create_dir_all(parent).expect("ERROR: could not create polya output directory!");
+    }
+
+    std::fs::write(bed_out, lines.join("\n") + "\n")
+        .unwrap_or_else(|_| panic!("ERROR: could not write BED12 -> {}", bed_out.display()));
+}
@@ -1,3 +1,5 @@
+use regex::Regex;
+
 use crate::{
     config::*,
     consts::*,
@@ -5,12 +7,15 @@ use crate::{
     executor::{job::Job, manager::ParallelExecutor},
 };
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Merge BAM files in a directory using samtools
 /// and index the merged BAMs
 ///
 /// # Arguments
+/// * `step` - The pipeline step merge is running under, used to resolve
+///   its thread/memory budget from `[params.<step>]` (see `ParallelExecutor::execute`).
 /// * `input_dir` - The directory containing the BAM files to merge.
 /// * `executor` - The executor to use for running the merge command.
 /// * `config` - The configuration to use for running the merge command.
@@ -20,70 +25,330 @@ use std::path::PathBuf;
 /// use std::path::PathBuf;
 /// use isopipe::core::samtools;
 /// use isopipe::executor::manager::ParallelExecutor;
-/// use isopipe::config::Config;
+/// use isopipe::config::{Config, PipelineStep};
 ///
 /// let input_dir = PathBuf::from("/path/to/bam/files");
 /// let mut executor = ParallelExecutor::new();
 /// let config = Config::default();
 ///
-/// samtools::merge(&input_dir, &mut executor, &config);
+/// samtools::merge(&PipelineStep::Lima, &input_dir, &mut executor, &config);
 /// ```
-pub fn merge(input_dir: &PathBuf, executor: &mut ParallelExecutor, config: &Config) {
-    const THREADS: u32 = 16;
-    const MEMORY: u32 = 8;
+pub fn merge(
+    step: &PipelineStep,
+    input_dir: &PathBuf,
+    executor: &mut ParallelExecutor,
+    config: &Config,
+) {
+    // INFO: mirrors ParallelExecutor::execute's own threads/memory lookup,
+    // so merge claims the same budget the executor will request for this
+    // step's batch instead of statically claiming 16 threads per job.
+    let threads = config
+        .get_param(*step, NUM_THREADS)
+        .map(|p| p.to_int() as u32)
+        .unwrap_or_else(|| {
+            config
+                .get_global_param(DEFAULT_THREADS)
+                .expect("ERROR: No default threads found in global parameters!")
+                .to_int() as u32
+        });
+    let memory = config
+        .get_param(*step, MEMORY)
+        .map(|p| p.to_int() as u32)
+        .unwrap_or_else(|| {
+            config
+                .get_global_param(DEFAULT_MEMORY)
+                .expect("ERROR: No default memory found in global parameters!")
+                .to_int() as u32
+        });
+
+    let sort_order = MergeSortOrder::from_str(config.get_merge_sort_order())
+        .unwrap_or_else(|e| panic!("{}", e));
+    let combine_headers = config.get_merge_combine_headers();
+
+    let mut flags = vec![sort_order.flag()];
+    if combine_headers {
+        flags.push("-c");
+        flags.push("-p");
+    }
+    let flags = flags
+        .into_iter()
+        .filter(|f| !f.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
 
     let mut jobs = Vec::new();
     let mut pbi = Vec::new();
+    let mut pending = Vec::new();
 
     let package = config.get_custom_package(SAMTOOLS);
-    let groups = scan_groups(input_dir);
+    let max_depth = config
+        .get_global_param(MAX_DEPTH)
+        .map(|param| param.to_int() as usize)
+        .unwrap_or(DEFAULT_MAX_DEPTH);
+    let groups = scan_groups(input_dir, max_depth, config.get_group_pattern());
 
     for (group, bams) in groups {
         if bams.len() > 1 {
             let merged = input_dir.join(format!("{}.ccs.{}", group, MERGED_BAM));
 
             if !merged.exists() {
-                // INFO: format of wildcard: {prefix}.{name}.ccs.*.bam
-                let wildcard = input_dir.join(format!("{}*{}", group, BAM));
+                // INFO: sources may be nested under per-sample subdirectories,
+                // so each is named explicitly rather than via a wildcard that
+                // only matches input_dir's own top level.
+                let sources = bams
+                    .iter()
+                    .map(|bam| bam.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
 
-                // INFO: if merged file does not existe, we merge and delete the unmerged files
+                // INFO: the merge job no longer deletes its sources itself;
+                // verify_and_delete() only removes them once the merged BAM's
+                // read count has been confirmed and its checksum recorded below.
                 let cmd = format!(
-                    "samtools merge -@{} {} {} && rm {}",
-                    THREADS,
+                    "samtools merge -@{} {} {} {}",
+                    threads,
+                    flags,
                     merged.display(),
-                    wildcard.display(),
-                    wildcard.display(),
+                    sources
                 );
 
-                pbi.push(merged);
-
-                let job = Job::from(cmd);
-                jobs.push(job);
+                jobs.push(Job::from(cmd));
+                pending.push((group, merged.clone(), bams));
+            } else {
+                // INFO: merged BAM already exists from a previous run; make
+                // sure it hasn't silently rotted since then.
+                verify_existing(input_dir, &group, &merged);
             }
+
+            pbi.push(merged);
         }
     }
 
-    if jobs.is_empty() {
-        return;
+    if !jobs.is_empty() {
+        executor.add_jobs(jobs).and_send(
+            config,
+            SAMTOOLS,
+            input_dir.clone(),
+            threads,
+            memory,
+            package,
+        );
     }
 
-    executor.add_jobs(jobs).and_send(
-        config,
-        SAMTOOLS,
-        input_dir.clone(),
-        THREADS,
-        MEMORY,
-        package,
-    );
+    let keep_sources = config.get_keep_sources();
+    for (group, merged, sources) in pending {
+        verify_and_delete(input_dir, &group, &merged, &sources, keep_sources);
+    }
 
     pbindex::pbindex(pbi, config, executor, input_dir);
 }
 
-/// Scan groups in the input directory and return a HashMap of group
-/// names to their corresponding BAM files.
+/// Sort-order assumption for `merge`'s BAM inputs, selecting which flag
+/// (if any) is passed to `samtools merge`: coordinate-sorted inputs need
+/// none, name-sorted ones need `-n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeSortOrder {
+    Coordinate,
+    Name,
+}
+
+impl MergeSortOrder {
+    /// Parse a `merge_sort_order` config value.
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "coordinate" => Ok(Self::Coordinate),
+            "name" => Ok(Self::Name),
+            _ => Err(format!(
+                "ERROR: Invalid merge_sort_order '{}', expected 'coordinate' or 'name'",
+                s
+            )),
+        }
+    }
+
+    /// The `samtools merge` flag for this sort order, empty for the
+    /// default coordinate-sorted case.
+    fn flag(&self) -> &'static str {
+        match self {
+            Self::Coordinate => "",
+            Self::Name => "-n",
+        }
+    }
+}
+
+/// Path to the BLAKE3 sidecar manifest recording a merged BAM's digest,
+/// keyed by its group so a later run can tell whether the file has
+/// silently changed since it was last verified.
+fn sidecar_path(input_dir: &Path, group: &str) -> PathBuf {
+    input_dir.join(format!("{}.ccs.{}.b3", group, MERGED_BAM))
+}
+
+/// BLAKE3 digest of `path`'s full contents, streamed in chunks so large
+/// BAMs aren't loaded into memory at once.
+fn blake3_digest(path: &Path) -> String {
+    let mut file = std::fs::File::open(path)
+        .unwrap_or_else(|e| panic!("ERROR: Failed to open {} for checksum: {}", path.display(), e));
+
+    let mut hasher = blake3::Hasher::new();
+    let mut buf = [0u8; 1 << 16];
+
+    loop {
+        let read = file
+            .read(&mut buf)
+            .expect(&format!("ERROR: Failed to read {} for checksum", path.display()));
+
+        if read == 0 {
+            break;
+        }
+
+        hasher.update(&buf[..read]);
+    }
+
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Total read count reported by `samtools flagstat` for `bam`.
+fn flagstat_total(bam: &Path) -> u64 {
+    let output = std::process::Command::new("samtools")
+        .arg("flagstat")
+        .arg(bam)
+        .output()
+        .unwrap_or_else(|e| panic!("ERROR: Failed to run samtools flagstat on {}: {}", bam.display(), e));
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().next())
+        .and_then(|count| count.parse::<u64>().ok())
+        .unwrap_or_else(|| panic!("ERROR: Could not parse samtools flagstat output for {}", bam.display()))
+}
+
+/// Re-check a merged BAM left over from a previous run against its
+/// recorded BLAKE3 digest, if one was ever recorded. A mismatch means the
+/// file has changed since we last trusted it (disk corruption, a manual
+/// edit, ...) and we'd rather abort loudly than build on top of it.
+fn verify_existing(input_dir: &Path, group: &str, merged: &Path) {
+    let Ok(recorded) = std::fs::read_to_string(sidecar_path(input_dir, group)) else {
+        return;
+    };
+
+    let digest = blake3_digest(merged);
+    if recorded.trim() != digest {
+        log::error!(
+            "ERROR: merged BAM '{}' checksum ({}) no longer matches its recorded digest ({}), refusing to trust it!",
+            merged.display(),
+            digest,
+            recorded.trim()
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Verify a freshly-merged BAM before touching the source files it was
+/// merged from: its total read count (via `samtools flagstat`) must match
+/// the sum of its sources', since a partial write or dropped reads would
+/// still leave the merge command exiting 0. Only once that holds do we
+/// record its BLAKE3 digest in [`sidecar_path`] and, depending on
+/// `keep_sources`, either delete the sources or relocate them into
+/// `input_dir/unmerged/`, mirroring each source's path relative to
+/// `input_dir` so same-named sources from different subdirectories don't
+/// collide, so they're still around for debugging or a downstream re-run.
+fn verify_and_delete(input_dir: &Path, group: &str, merged: &Path, sources: &[PathBuf], keep_sources: bool) {
+    let merged_count = flagstat_total(merged);
+    let source_count: u64 = sources.iter().map(|bam| flagstat_total(bam)).sum();
+
+    if merged_count != source_count {
+        log::error!(
+            "ERROR: merged BAM '{}' has {} reads but its {} source file(s) have {} combined, refusing to touch them!",
+            merged.display(),
+            merged_count,
+            sources.len(),
+            source_count
+        );
+        std::process::exit(1);
+    }
+
+    let digest = blake3_digest(merged);
+    std::fs::write(sidecar_path(input_dir, group), &digest).unwrap_or_else(|e| {
+        panic!(
+            "ERROR: Failed to write checksum sidecar for {}: {}",
+            merged.display(),
+            e
+        )
+    });
+
+    if keep_sources {
+        let unmerged_dir = input_dir.join(UNMERGED_DIR);
+        std::fs::create_dir_all(&unmerged_dir)
+            .unwrap_or_else(|e| panic!("ERROR: Failed to create {}: {}", unmerged_dir.display(), e));
+
+        for bam in sources {
+            // INFO: namespaced by the source's path relative to input_dir,
+            // not just its basename -- two samples nested under different
+            // subdirectories (see `walk_bams`/`scan_groups`) can otherwise
+            // share a basename and clobber each other on rename.
+            let relative = bam.strip_prefix(input_dir).unwrap_or(bam);
+            let destination = unmerged_dir.join(relative);
+
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent).unwrap_or_else(|e| {
+                    panic!("ERROR: Failed to create {}: {}", parent.display(), e)
+                });
+            }
+
+            std::fs::rename(bam, &destination).unwrap_or_else(|e| {
+                panic!(
+                    "ERROR: Failed to relocate verified source BAM {} into {}: {}",
+                    bam.display(),
+                    destination.display(),
+                    e
+                )
+            });
+        }
+
+        log::info!(
+            "INFO [SAMTOOLS]: verified merged BAM '{}' ({} reads, blake3 {}), kept {} source file(s) in '{}'",
+            merged.display(),
+            merged_count,
+            digest,
+            sources.len(),
+            unmerged_dir.display()
+        );
+    } else {
+        for bam in sources {
+            std::fs::remove_file(bam)
+                .unwrap_or_else(|e| panic!("ERROR: Failed to delete verified source BAM {}: {}", bam.display(), e));
+        }
+
+        log::info!(
+            "INFO [SAMTOOLS]: verified merged BAM '{}' ({} reads, blake3 {}), deleted {} source file(s)",
+            merged.display(),
+            merged_count,
+            digest,
+            sources.len()
+        );
+    }
+}
+
+/// Scan `input_dir` and every subdirectory up to `max_depth` levels deep,
+/// and return a HashMap of group names to their corresponding BAM files.
+///
+/// Demultiplexed output is often written into per-sample or per-lane
+/// subfolders, so a flat `read_dir` over `input_dir` alone would miss
+/// anything nested; this descends the tree the way a recursive resource
+/// collector flattens a directory into a keyed map of leaf files. A BAM
+/// directly under `input_dir` groups by its `{name}` token alone; one
+/// found under a subdirectory is keyed by `{parent}.{name}` instead, so
+/// two samples that happen to share a `{name}` token under different
+/// subtrees don't collapse into one group.
 ///
 /// # Arguments
 /// * `input_dir` - The input directory to scan for BAM files.
+/// * `max_depth` - How many levels of subdirectories to descend into;
+///   `0` scans only `input_dir` itself.
+/// * `group_pattern` - Regex with a named `group` capture used to pull the
+///   group name out of each BAM's basename; see [`Config::group_pattern`].
+///   A basename that doesn't match is logged and skipped rather than
+///   aborting the whole scan.
 ///
 /// # Returns
 /// A HashMap of group names to their corresponding BAM files.
@@ -91,40 +356,105 @@ pub fn merge(input_dir: &PathBuf, executor: &mut ParallelExecutor, config: &Conf
 /// # Examples
 /// ```
 /// let input_dir = PathBuf::from("/path/to/input");
-/// let groups = scan_groups(&input_dir);
+/// let groups = scan_groups(&input_dir, 4, DEFAULT_GROUP_PATTERN);
 /// assert_eq!(groups.len(), 2);
 /// assert_eq!(groups["group1"].len(), 3);
 /// assert_eq!(groups["group2"].len(), 2);
 /// ```
-fn scan_groups(input_dir: &PathBuf) -> HashMap<String, Vec<PathBuf>> {
+fn scan_groups(input_dir: &Path, max_depth: usize, group_pattern: &str) -> HashMap<String, Vec<PathBuf>> {
+    let regex = Regex::new(group_pattern)
+        .unwrap_or_else(|e| panic!("ERROR: Invalid group_pattern regex '{}': {}", group_pattern, e));
+
     let mut groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
 
-    for entry in std::fs::read_dir(input_dir)
-        .expect("Failed to read assets directory")
-        .flatten()
-        .filter(|entry| {
-            entry
-                .path()
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| ext.eq_ignore_ascii_case(BAM))
-                .unwrap_or(false)
+    for bam in walk_bams(input_dir, max_depth) {
+        let filename = bam
+            .file_name()
+            .and_then(|name| name.to_str())
+            .expect(&format!("ERROR: Failed to get filename of {}", bam.display()));
+
+        let basename = match extract_group(&regex, filename) {
+            Ok(basename) => basename,
+            Err(e) => {
+                log::error!("{}", e);
+                continue;
+            }
+        };
+
+        let key = match bam.parent() {
+            Some(parent) if parent != input_dir => {
+                let sample = parent
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .expect(&format!("ERROR: Failed to get parent dir of {}", bam.display()));
+
+                format!("{}.{}", sample, basename)
+            }
+            _ => basename,
+        };
+
+        groups.entry(key).or_insert(Vec::new()).push(bam);
+    }
+
+    groups
+}
+
+/// Extract a merge group name from a BAM's `basename` via `regex`'s named
+/// `group` capture (see [`Config::group_pattern`]), instead of the
+/// hardcoded `split(".").nth(1)` this replaced.
+///
+/// # Returns
+///
+/// The captured group name, or a structured error describing why
+/// `basename` didn't match, so one oddly-named file doesn't `.expect()`-panic
+/// and abort the whole run.
+fn extract_group(regex: &Regex, basename: &str) -> Result<String, String> {
+    regex
+        .captures(basename)
+        .and_then(|captures| captures.name("group"))
+        .map(|m| m.as_str().to_string())
+        .ok_or_else(|| {
+            format!(
+                "ERROR: '{}' did not match group_pattern '{}' (expected a named 'group' capture), skipping",
+                basename,
+                regex.as_str()
+            )
         })
+}
+
+/// Recursively collect every `.bam` file under `dir`, descending into
+/// subdirectories up to `max_depth` levels deep.
+fn walk_bams(dir: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+
+    for entry in std::fs::read_dir(dir)
+        .expect(&format!("ERROR: Failed to read directory {}", dir.display()))
+        .flatten()
     {
-        // INFO: basename -> {prefix}.{name}.{*}.bam -> {name}
-        let bam = entry.path();
-        let basename = bam
-            .to_string_lossy()
-            .split(".")
-            .nth(1)
-            .expect(&format!(
-                "ERROR: Failed to get basename from {}",
-                entry.path().display()
-            ))
-            .to_string();
-
-        groups.entry(basename).or_insert(Vec::new()).push(bam);
+        let path = entry.path();
+
+        if path.is_dir() {
+            // INFO: don't re-discover sources `verify_and_delete` already
+            // relocated out of a prior run as a fresh group to merge.
+            if path.file_name().and_then(|name| name.to_str()) == Some(UNMERGED_DIR) {
+                continue;
+            }
+
+            if max_depth > 0 {
+                found.extend(walk_bams(&path, max_depth - 1));
+            }
+            continue;
+        }
+
+        if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case(BAM))
+            .unwrap_or(false)
+        {
+            found.push(path);
+        }
     }
 
-    groups
+    found
 }
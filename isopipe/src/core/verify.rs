@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use config::OverlapType;
+use iso_polya::utils::get_sequences;
+use packbed::{record::Bed6, unpack};
+
+/// One failed integrity check against a step's emitted artifacts: which
+/// file it was found in, which record triggered it, and why.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let error = VerificationError {
+///     file: PathBuf::from("transcripts.fa"),
+///     record_id: "tx.1".into(),
+///     reason: "sequence length 120 does not match BED6 span 123".into(),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationError {
+    pub file: PathBuf,
+    pub record_id: String,
+    pub reason: String,
+}
+
+impl VerificationError {
+    fn new(file: &Path, record_id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            record_id: record_id.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "VERIFY: {} [{}]: {}",
+            self.file.display(),
+            self.record_id,
+            self.reason
+        )
+    }
+}
+
+/// Parse a FASTA file into `(id, sequence)` pairs, in file order. A
+/// `.gz` path is decoded as BGZF, the format `extract` compresses to.
+fn read_fasta(path: &Path) -> Vec<(String, String)> {
+    let contents = if path.extension().map_or(false, |ext| ext == "gz") {
+        let file = std::fs::File::open(path)
+            .unwrap_or_else(|_| panic!("ERROR: could not read FASTA -> {}", path.display()));
+        let mut contents = String::new();
+        std::io::Read::read_to_string(&mut noodles_bgzf::Reader::new(file), &mut contents)
+            .unwrap_or_else(|_| panic!("ERROR: could not read FASTA -> {}", path.display()));
+        contents
+    } else {
+        std::fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("ERROR: could not read FASTA -> {}", path.display()))
+    };
+
+    let mut records = Vec::new();
+    let mut id = String::new();
+    let mut seq = String::new();
+
+    for line in contents.lines() {
+        if let Some(rest) = line.strip_prefix('>') {
+            if !id.is_empty() {
+                records.push((id.clone(), std::mem::take(&mut seq)));
+            }
+            id = rest.trim().to_string();
+        } else {
+            seq.push_str(line.trim());
+        }
+    }
+
+    if !id.is_empty() {
+        records.push((id, seq));
+    }
+
+    records
+}
+
+/// Verify [`crate::core::orf::extract`]'s FASTA output against the BED6
+/// it was extracted from:
+///
+/// * every record's sequence length equals `tx.coord.1 - tx.coord.0`
+/// * every record contains only `{A,C,G,T,N}` (case-insensitive)
+/// * transcript IDs are unique and match the source BED6 IDs exactly
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let errors = verify_extract(&fasta, &bed);
+/// ```
+pub fn verify_extract(fasta: &Path, bed: &Path) -> Vec<VerificationError> {
+    let mut errors = Vec::new();
+
+    let grouped = unpack::<Bed6, _>(vec![bed.to_path_buf()], OverlapType::Exon, false)
+        .unwrap_or_else(|_| panic!("ERROR: could not unpack reads -> {}", bed.display()));
+
+    let mut expected_lens = std::collections::HashMap::new();
+    for (_chr, transcripts) in &grouped {
+        for tx in transcripts {
+            expected_lens.insert(tx.id.clone(), tx.coord.1 - tx.coord.0);
+        }
+    }
+
+    let records = read_fasta(fasta);
+    let mut seen = HashSet::new();
+
+    for (id, seq) in &records {
+        if !seen.insert(id.clone()) {
+            errors.push(VerificationError::new(
+                fasta,
+                id,
+                "duplicate transcript id in FASTA output",
+            ));
+        }
+
+        match expected_lens.get(id) {
+            Some(&expected) => {
+                if seq.len() as u32 != expected {
+                    errors.push(VerificationError::new(
+                        fasta,
+                        id,
+                        format!(
+                            "sequence length {} does not match BED6 span {}",
+                            seq.len(),
+                            expected
+                        ),
+                    ));
+                }
+            }
+            None => errors.push(VerificationError::new(
+                fasta,
+                id,
+                "transcript id not found in source BED6",
+            )),
+        }
+
+        if let Some(bad) = seq
+            .chars()
+            .find(|c| !matches!(c.to_ascii_uppercase(), 'A' | 'C' | 'G' | 'T' | 'N'))
+        {
+            errors.push(VerificationError::new(
+                fasta,
+                id,
+                format!("sequence contains invalid character '{}'", bad),
+            ));
+        }
+    }
+
+    errors
+}
+
+/// One parsed BED12 line: the fields [`verify_polya`] needs to bounds-check.
+struct Bed12Record {
+    chrom: String,
+    chrom_end: u64,
+    name: String,
+}
+
+fn read_bed12(path: &Path) -> Vec<Bed12Record> {
+    let contents = std::fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("ERROR: could not read BED12 -> {}", path.display()));
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let chrom = fields.first()?.to_string();
+            let chrom_end = fields.get(2)?.parse().ok()?;
+            let name = fields.get(3).map(|s| s.to_string()).unwrap_or_default();
+
+            Some(Bed12Record {
+                chrom,
+                chrom_end,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Verify [`crate::core::polya::polya`]'s final `*.corrected.good.bed`
+/// BED12 output: every record's block coordinates must fall within its
+/// contig's length, read from the `.2bit` genome.
+///
+/// Does not check record-count monotonicity across a first-pass ->
+/// corrected -> second-pass sequence of files: `polya()`'s native
+/// `filter_to_bed12` pass (see [`crate::core::polya_sam`]) replaced the
+/// old `filter.pl`/`bedtools` pipeline's multiple intermediate files with
+/// a single filtering pass per clustering category, so there is no
+/// longer a multi-file sequence for that check to apply to.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let errors = verify_polya(&bed, &twobit);
+/// ```
+pub fn verify_polya(bed: &Path, twobit: &Path) -> Vec<VerificationError> {
+    let mut errors = Vec::new();
+
+    let (genome, _) = get_sequences(twobit.to_path_buf())
+        .unwrap_or_else(|_| panic!("ERROR: could not get sequences from .2bit -> {}", twobit.display()));
+
+    for record in read_bed12(bed) {
+        match genome.get(&record.chrom) {
+            Some(contig) => {
+                if record.chrom_end > contig.len() as u64 {
+                    errors.push(VerificationError::new(
+                        bed,
+                        &record.name,
+                        format!(
+                            "chrom_end {} exceeds contig '{}' length {}",
+                            record.chrom_end,
+                            record.chrom,
+                            contig.len()
+                        ),
+                    ));
+                }
+            }
+            None => errors.push(VerificationError::new(
+                bed,
+                &record.name,
+                format!("contig '{}' not found in .2bit genome", record.chrom),
+            )),
+        }
+    }
+
+    errors
+}
@@ -1,9 +1,41 @@
 use crate::config::PipelineStep;
-use std::fmt::Write;
+use std::path::PathBuf;
+
+/// One program invocation within a [`Job`]: a program name followed by its
+/// argument vector. A job with more than one stage is a pipe (`a | b`).
+#[derive(Debug, Clone, Default)]
+pub struct Stage {
+    pub program: String,
+    pub argv: Vec<String>,
+}
+
+impl Stage {
+    /// Render this stage as a shell word list: the program name verbatim
+    /// and unquoted (so a multi-word name like `isoseq refine` keeps its
+    /// subcommand) followed by each argv entry quoted via [`shell_quote`].
+    fn render(&self) -> String {
+        let mut rendered = self.program.clone();
+        for arg in &self.argv {
+            rendered.push(' ');
+            rendered.push_str(&shell_quote(arg));
+        }
+        rendered
+    }
+}
 
 /// Struct to represent a job to be executed
 /// by the pipeline
 ///
+/// Built up either as a sequence of [`Stage`]s (a program name plus a
+/// quoted argv, optionally piped into further stages and redirected to
+/// files) via [`Job::task`]/[`Job::program`]/[`Job::arg`]/[`Job::args`], or
+/// as a single opaque shell line via [`Job::from`] for call sites that
+/// predate this model and already assemble their own multi-command shell
+/// strings. [`Job::render`] is the single place that turns either
+/// representation into the command line handed to the Nextflow/Para
+/// back-ends today, and that a future direct-exec back-end could bypass
+/// entirely for the structured case.
+///
 /// # Example
 ///
 /// ```rust, no_run
@@ -15,11 +47,33 @@ use std::fmt::Write;
 ///     .arg("output.bam")
 ///     .arg("chunks");
 ///
-/// assert_eq!(job.cmd, "ccs input.bam output.bam chunks");
+/// assert_eq!(job.render(), "ccs input.bam output.bam chunks");
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Job {
-    pub cmd: String,
+    /// Structured stages making up this job's pipeline, rendered by
+    /// [`Job::render`] unless `raw` is set.
+    pub stages: Vec<Stage>,
+    /// Path to redirect the last stage's stdout into (`render()` appends
+    /// `> path`).
+    pub stdout: Option<PathBuf>,
+    /// Opaque pre-assembled shell line, for call sites built before the
+    /// structured stage model. Takes priority over `stages` in `render()`.
+    pub raw: Option<String>,
+    /// Input paths this job reads from; validated to exist/be readable
+    /// before the job is ever dispatched to an external tool.
+    pub inputs: Vec<PathBuf>,
+    /// Output paths this job is expected to produce; the parent
+    /// directory of each must be writable.
+    pub outputs: Vec<PathBuf>,
+    /// Flags that must be present in the rendered command (e.g. `--log-file`).
+    pub required_flags: Vec<String>,
+    /// Stable id used to reference this job from another job's
+    /// `depends_on`; jobs without one are addressed by their position
+    /// in the batch when the dependency graph is built.
+    pub id: String,
+    /// Ids of jobs that must complete before this one is scheduled.
+    pub depends_on: Vec<String>,
 }
 
 impl Job {
@@ -32,28 +86,153 @@ impl Job {
     ///
     /// let job = Job::new();
     ///
-    /// assert_eq!(job.cmd, "");
+    /// assert_eq!(job.render(), "");
     /// ```
     pub fn new() -> Self {
-        Self { cmd: String::new() }
+        Self::default()
     }
 
-    /// Create a new job from a command string
+    /// Create a job from a pre-assembled shell line, bypassing the
+    /// structured stage model. Kept for call sites that already build
+    /// their own multi-command strings (e.g. `a && b`); new job-building
+    /// code should prefer [`Job::task`]/[`Job::program`] so arguments get
+    /// quoted instead of interpolated straight into a shell command.
     ///
     /// # Example
     ///
     /// ```rust, no_run
     /// use isopipe::executor::job::Job;
     ///
-    /// let job = Job::from("ccs input.bam output.bam chunks");
+    /// let job = Job::from("ccs input.bam output.bam chunks".to_string());
     ///
-    /// assert_eq!(job.cmd, "ccs input.bam output.bam chunks");
+    /// assert_eq!(job.render(), "ccs input.bam output.bam chunks");
     /// ```
     pub fn from(cmd: String) -> Self {
-        Self { cmd }
+        Self {
+            raw: Some(cmd),
+            ..Self::new()
+        }
+    }
+
+    /// Declare a path this job reads from, to be checked by [`Job::validate`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    /// use std::path::PathBuf;
+    ///
+    /// let job = Job::new().input(PathBuf::from("input.bam"));
+    /// ```
+    pub fn input(mut self, path: PathBuf) -> Self {
+        self.inputs.push(path);
+        self
+    }
+
+    /// Declare a path this job is expected to produce, to be checked by
+    /// [`Job::validate`].
+    pub fn output(mut self, path: PathBuf) -> Self {
+        self.outputs.push(path);
+        self
+    }
+
+    /// Declare a flag that must be present in the rendered command, to be
+    /// checked by [`Job::validate`].
+    pub fn require_flag(mut self, flag: &str) -> Self {
+        self.required_flags.push(flag.to_string());
+        self
+    }
+
+    /// Give this job a stable id so other jobs can name it in
+    /// [`Job::depends_on`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    ///
+    /// let job = Job::new().id("pbindex:sample.bam");
+    /// ```
+    pub fn id<D: std::fmt::Display>(mut self, id: D) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    /// Declare a prerequisite job id that must complete before this job
+    /// is scheduled; see [`topo_sort`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    ///
+    /// let job = Job::new().depends_on("pbindex:sample.bam");
+    /// ```
+    pub fn depends_on<D: std::fmt::Display>(mut self, id: D) -> Self {
+        self.depends_on.push(id.to_string());
+        self
+    }
+
+    /// Validate this job's declared argument signature before it is
+    /// handed to any external tool: every input path must exist and be
+    /// readable, every output's parent directory must be writable, and
+    /// every required flag must actually appear in the rendered command.
+    ///
+    /// # Returns
+    ///
+    /// A list of human-readable error strings; empty if the job is valid.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    ///
+    /// let job = Job::new().input("missing.bam".into());
+    /// assert_eq!(job.validate().len(), 1);
+    /// ```
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let rendered = self.render();
+
+        for input in &self.inputs {
+            if !input.exists() {
+                errors.push(format!(
+                    "ERROR: job '{}' requires input '{}' which does not exist",
+                    rendered,
+                    input.display()
+                ));
+            }
+        }
+
+        for output in &self.outputs {
+            let writable = output
+                .parent()
+                .map(|parent| parent.as_os_str().is_empty() || parent.exists())
+                .unwrap_or(true);
+
+            if !writable {
+                errors.push(format!(
+                    "ERROR: job '{}' writes to '{}' whose parent directory does not exist",
+                    rendered,
+                    output.display()
+                ));
+            }
+        }
+
+        for flag in &self.required_flags {
+            if !rendered.contains(flag.as_str()) {
+                errors.push(format!(
+                    "ERROR: job '{}' is missing required flag '{}'",
+                    rendered, flag
+                ));
+            }
+        }
+
+        errors
     }
 
-    /// Add a task to the job
+    /// Start a new stage running `step`'s tool, pipe-chained after any
+    /// stage already built on this job.
     ///
     /// # Example
     ///
@@ -63,9 +242,9 @@ impl Job {
     /// let job = Job::new()
     ///    .task(PipelineStep::Ccs);
     ///
-    /// assert_eq!(job.cmd, "ccs");
+    /// assert_eq!(job.render(), "ccs");
     /// ```
-    pub fn task(mut self, step: PipelineStep) -> Self {
+    pub fn task(self, step: PipelineStep) -> Self {
         let step_cmd = match step {
             PipelineStep::Ccs => "ccs",
             PipelineStep::Lima => "lima",
@@ -73,15 +252,110 @@ impl Job {
             PipelineStep::Cluster => "isoseq cluster",
             PipelineStep::Polya => "",
             PipelineStep::Minimap => "minimap2",
+            // `LoadGenome` resolves its program name from config at call
+            // time (see `core::run_step`'s `LoadGenome` arm), same as
+            // `External`, since there's no single built-in tool for it.
+            PipelineStep::LoadGenome => "",
             PipelineStep::Fusion => "isotools iso-fusion",
             PipelineStep::Orf => "",
+            PipelineStep::Custom => "",
+            // External steps resolve their program name from config at
+            // call time (see `core::run_step`'s `External` arm), not from
+            // this hardcoded table, since the name isn't known statically.
+            PipelineStep::External(_) => "",
         };
 
-        self.cmd.push_str(step_cmd);
+        self.program(step_cmd)
+    }
+
+    /// Start a new stage running `program` (an external tool not modeled
+    /// as a [`PipelineStep`], e.g. `cat`), pipe-chained after any stage
+    /// already built on this job.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    ///
+    /// let job = Job::new().program("cat");
+    ///
+    /// assert_eq!(job.render(), "cat");
+    /// ```
+    pub fn program<D: std::fmt::Display>(mut self, program: D) -> Self {
+        self.stages.push(Stage {
+            program: program.to_string(),
+            argv: Vec::new(),
+        });
+        self
+    }
+
+    /// Pipe the output of the job built so far into a new stage running
+    /// `program`, equivalent to `... | program`.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    ///
+    /// let job = Job::new().program("samtools view").pipe_to("sort");
+    ///
+    /// assert_eq!(job.render(), "samtools view | sort");
+    /// ```
+    pub fn pipe_to<D: std::fmt::Display>(self, program: D) -> Self {
+        self.program(program)
+    }
+
+    /// Redirect the final stage's stdout to `path` (`render()` appends
+    /// `> path`).
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    /// use std::path::PathBuf;
+    ///
+    /// let job = Job::new().program("cat").redirect_stdout(PathBuf::from("out.bed"));
+    ///
+    /// assert_eq!(job.render(), "cat > out.bed");
+    /// ```
+    pub fn redirect_stdout(mut self, path: PathBuf) -> Self {
+        self.outputs.push(path.clone());
+        self.stdout = Some(path);
         self
     }
 
-    /// Add an argument to the job
+    /// Expand `pattern` against the filesystem in-process (`*` wildcards
+    /// within a path segment, no shell involved) and append each
+    /// match as its own argv entry to the current stage; every match is
+    /// also recorded as a declared input for [`Job::validate`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    ///
+    /// let job = Job::new().program("cat").glob("fusions/*/out.bed");
+    /// ```
+    pub fn glob(mut self, pattern: &str) -> Self {
+        let matches = expand_glob(pattern);
+
+        let stage = self
+            .stages
+            .last_mut()
+            .expect("ERROR: glob() called before task()/program()");
+
+        for path in &matches {
+            stage.argv.push(path.display().to_string());
+        }
+
+        self.inputs.extend(matches);
+        self
+    }
+
+    /// Add an argument to the job's current stage. Splits `arg` on
+    /// whitespace into separate argv entries, matching the historical
+    /// behavior of callers that pass an already space-joined flag string
+    /// (e.g. `config.get_step_args(...)`).
     ///
     /// # Example
     ///
@@ -92,16 +366,22 @@ impl Job {
     ///     .task(PipelineStep::Ccs)
     ///     .arg("input.bam");
     ///
-    /// assert_eq!(job.cmd, "ccs input.bam");
+    /// assert_eq!(job.render(), "ccs input.bam");
     /// ```
     pub fn arg<D: std::fmt::Display>(mut self, arg: D) -> Self {
-        self.cmd.push(' ');
-        write!(&mut self.cmd, "{arg}").expect("ERROR: Failed to append arg to cmd!");
+        let arg = arg.to_string();
+        let stage = self
+            .stages
+            .last_mut()
+            .expect("ERROR: arg() called before task()/program()");
+
+        stage.argv.extend(arg.split_whitespace().map(String::from));
 
         self
     }
 
-    /// Add multiple arguments to the job
+    /// Add multiple arguments to the job's current stage, each kept as
+    /// its own argv entry (no whitespace splitting).
     ///
     /// # Example
     ///
@@ -112,17 +392,281 @@ impl Job {
     ///     .task(PipelineStep::Ccs)
     ///     .args(&["input.bam", "output.bam", "chunks"]);
     ///
-    /// assert_eq!(job.cmd, "ccs input.bam output.bam chunks");
+    /// assert_eq!(job.render(), "ccs input.bam output.bam chunks");
     /// ```
     pub fn args(mut self, args: &[&str]) -> Self {
-        for arg in args {
-            self.cmd.push(' ');
-            self.cmd.push_str(arg);
-        }
+        let stage = self
+            .stages
+            .last_mut()
+            .expect("ERROR: args() called before task()/program()");
+
+        stage.argv.extend(args.iter().map(|arg| arg.to_string()));
+        self
+    }
+
+    /// Add multiple arguments to the job's current stage from an owned
+    /// argv (e.g. [`crate::config::StepParams::argv`]), each kept as its
+    /// own entry with no whitespace splitting, unlike [`Job::arg`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    /// use std::ffi::OsString;
+    ///
+    /// let job = Job::new()
+    ///     .task(PipelineStep::Ccs)
+    ///     .argv(vec![OsString::from("--min-rq"), OsString::from("0.9")]);
+    ///
+    /// assert_eq!(job.render(), "ccs --min-rq 0.9");
+    /// ```
+    pub fn argv(mut self, args: Vec<std::ffi::OsString>) -> Self {
+        let stage = self
+            .stages
+            .last_mut()
+            .expect("ERROR: argv() called before task()/program()");
+
+        stage
+            .argv
+            .extend(args.into_iter().map(|arg| arg.to_string_lossy().into_owned()));
         self
     }
 
-    pub fn cmd(&self) -> &str {
-        &self.cmd
+    /// Render this job into the command line handed to the Nextflow/Para
+    /// back-ends: `raw` verbatim if set, otherwise every stage rendered
+    /// and piped together with `stdout` appended as a `>` redirect.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// use isopipe::executor::job::Job;
+    ///
+    /// let job = Job::new().task(PipelineStep::Ccs).arg("in.bam");
+    /// assert_eq!(job.render(), "ccs in.bam");
+    /// ```
+    pub fn render(&self) -> String {
+        if let Some(raw) = &self.raw {
+            return raw.clone();
+        }
+
+        let mut rendered = self
+            .stages
+            .iter()
+            .map(Stage::render)
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        if let Some(path) = &self.stdout {
+            rendered.push_str(" > ");
+            rendered.push_str(&shell_quote(&path.display().to_string()));
+        }
+
+        rendered
     }
 }
+
+/// Quote `token` for safe inclusion in a shell command line: left
+/// untouched if it only contains characters that never need escaping
+/// (alphanumerics plus a conservative set of path/flag punctuation),
+/// otherwise wrapped in single quotes with any embedded quote escaped.
+fn shell_quote(token: &str) -> String {
+    let safe = !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ':' | ',' | '=' | '+'));
+
+    if safe {
+        token.to_string()
+    } else {
+        format!("'{}'", token.replace('\'', r"'\''"))
+    }
+}
+
+/// Expand `pattern` against the filesystem without a shell: each `/`
+/// separated segment containing a `*` is matched against real directory
+/// entries (one `*` standing in for any run of characters within that
+/// segment); segments without a `*` pass through unchanged. Only paths
+/// that exist on disk are returned, sorted for a deterministic job order.
+fn expand_glob(pattern: &str) -> Vec<PathBuf> {
+    let is_absolute = pattern.starts_with('/');
+    let mut frontier = vec![PathBuf::new()];
+
+    for segment in pattern.trim_start_matches('/').split('/') {
+        if segment.is_empty() {
+            continue;
+        }
+
+        if !segment.contains('*') {
+            frontier = frontier.into_iter().map(|base| base.join(segment)).collect();
+            continue;
+        }
+
+        let mut next = Vec::new();
+        for base in &frontier {
+            let dir = if is_absolute {
+                PathBuf::from("/").join(base)
+            } else if base.as_os_str().is_empty() {
+                PathBuf::from(".")
+            } else {
+                base.clone()
+            };
+
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+
+                if matches_wildcard(segment, &name) {
+                    next.push(base.join(name.as_ref()));
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    let mut matches: Vec<PathBuf> = frontier
+        .into_iter()
+        .map(|path| if is_absolute { PathBuf::from("/").join(path) } else { path })
+        .filter(|path| path.exists())
+        .collect();
+
+    matches.sort();
+    matches
+}
+
+/// Match `name` against `pattern`, a single path segment where `*` stands
+/// for any run of characters (including none).
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+
+    let mut pos = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+
+        if i == 0 {
+            if !name[pos..].starts_with(part) {
+                return false;
+            }
+            pos += part.len();
+        } else if i == parts.len() - 1 {
+            return pos <= name.len() - part.len() && name[pos..].ends_with(part);
+        } else {
+            match name[pos..].find(part) {
+                Some(found) => pos += found + part.len(),
+                None => return false,
+            }
+        }
+    }
+
+    true
+}
+
+/// Validate a batch of jobs, collecting every error across all of them
+/// instead of failing on the first. Call this before handing `jobs` to
+/// the executor so a missing input or an unwritable output dir is caught
+/// during planning rather than as a mid-run tool failure.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// use isopipe::executor::job::{Job, validate_jobs};
+///
+/// let jobs = vec![Job::new()];
+/// let errors = validate_jobs(&jobs);
+/// assert!(errors.is_empty());
+/// ```
+pub fn validate_jobs(jobs: &[Job]) -> Vec<String> {
+    jobs.iter().flat_map(|job| job.validate()).collect()
+}
+
+/// Arrange `jobs` into dependency "waves" (a ready-set schedule): wave 0
+/// holds every job with no unmet prerequisite, wave 1 holds the jobs that
+/// become ready once wave 0 completes, and so on via Kahn's algorithm.
+/// Jobs without an explicit [`Job::id`]/[`Job::depends_on`] always land in
+/// wave 0, preserving today's flat, unordered dispatch.
+///
+/// # Returns
+///
+/// `Ok(waves)` in schedule order, or `Err` naming an unknown dependency id
+/// or reporting a cycle if the graph can't be fully ordered.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// use isopipe::executor::job::{Job, topo_sort};
+///
+/// let a = Job::new().id("a");
+/// let b = Job::new().id("b").depends_on("a");
+/// let waves = topo_sort(&[a, b]).expect("ERROR: cyclic job graph");
+/// assert_eq!(waves.len(), 2);
+/// ```
+pub fn topo_sort(jobs: &[Job]) -> Result<Vec<Vec<Job>>, String> {
+    use std::collections::{HashMap, VecDeque};
+
+    let effective_id = |index: usize, job: &Job| -> String {
+        if job.id.is_empty() {
+            format!("#{}", index)
+        } else {
+            job.id.clone()
+        }
+    };
+
+    let ids: Vec<String> = jobs
+        .iter()
+        .enumerate()
+        .map(|(index, job)| effective_id(index, job))
+        .collect();
+    let index_of: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (id.as_str(), i)).collect();
+
+    let mut indegree = vec![0usize; jobs.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); jobs.len()];
+
+    for (index, job) in jobs.iter().enumerate() {
+        for dep in &job.depends_on {
+            let dep_index = *index_of.get(dep.as_str()).ok_or_else(|| {
+                format!(
+                    "ERROR: job '{}' depends on unknown job id '{}'",
+                    ids[index], dep
+                )
+            })?;
+
+            indegree[index] += 1;
+            dependents[dep_index].push(index);
+        }
+    }
+
+    let mut remaining = indegree.clone();
+    let mut ready: VecDeque<usize> = (0..jobs.len()).filter(|&i| indegree[i] == 0).collect();
+    let mut waves = Vec::new();
+    let mut scheduled = 0;
+
+    while !ready.is_empty() {
+        let wave: Vec<usize> = ready.drain(..).collect();
+        scheduled += wave.len();
+
+        for &index in &wave {
+            for &dependent in &dependents[index] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    ready.push_back(dependent);
+                }
+            }
+        }
+
+        waves.push(wave.into_iter().map(|index| jobs[index].clone()).collect());
+    }
+
+    if scheduled != jobs.len() {
+        return Err("ERROR: cycle detected in job dependency graph".to_string());
+    }
+
+    Ok(waves)
+}
@@ -6,6 +6,8 @@ use crate::{
     config::{Config, PipelineStep},
     consts::*,
     executor::job::Job,
+    freshness::FreshnessCache,
+    jobserver::JobServer,
 };
 
 #[derive(Debug, Clone)]
@@ -16,6 +18,13 @@ pub struct ParallelExecutor {
     pub jobs: Vec<Job>,
     /// List of arguments to pass to the parallel manager
     pub args: Vec<String>,
+    /// Global token pool fed by `--jobs N`. The `Local` manager takes a
+    /// token for every job it starts, bounding how many of its subprocesses
+    /// run at once across the whole step DAG; `Para` and `Nextflow` only
+    /// export its `MAKEFLAGS` to the command they launch and take no tokens
+    /// for their jobs. `None` means no global ceiling beyond each
+    /// manager's own per-step concurrency.
+    pub jobserver: Option<JobServer>,
 }
 
 impl ParallelExecutor {
@@ -34,6 +43,43 @@ impl ParallelExecutor {
             manager,
             jobs: Vec::new(),
             args: Vec::new(),
+            jobserver: None,
+        }
+    }
+
+    /// Attach a global token pool, bounding how many external tool
+    /// subprocesses this executor (and every clone made from it, e.g.
+    /// per-step clones in [`crate::core::run_graph`]) may run at once
+    /// under the `Local` manager (see [`ParallelExecutor::jobserver`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// let mut executor = ParallelManager::Local.init();
+    /// executor.with_jobserver(JobServer::new(8).expect("ERROR: Failed to create jobserver pipe"));
+    /// ```
+    pub fn with_jobserver(&mut self, jobserver: JobServer) -> &mut Self {
+        self.jobserver = Some(jobserver);
+
+        self
+    }
+
+    /// Derive a fresh executor for dispatching one step concurrently with
+    /// others (see [`crate::core::run_graph`]): same manager and jobserver
+    /// pool, but an empty job/arg list so sibling steps never see each
+    /// other's jobs.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// let mut local_executor = executor.clone_for_step();
+    /// ```
+    pub fn clone_for_step(&self) -> Self {
+        Self {
+            manager: self.manager.clone(),
+            jobs: Vec::new(),
+            args: Vec::new(),
+            jobserver: self.jobserver,
         }
     }
 
@@ -113,7 +159,20 @@ impl ParallelExecutor {
     /// executor.execute();
     /// ```
     pub fn execute(&mut self, config: &Config, step: &PipelineStep, global_output_dir: PathBuf) {
-        let jobs = write_jobs(self.jobs.clone(), global_output_dir.clone());
+        let mut cache = FreshnessCache::load(&global_output_dir);
+        let dirty = cache.partition(self.jobs.clone());
+
+        if dirty.is_empty() {
+            log::info!("INFO: all jobs for {} are fresh, nothing to dispatch", step);
+            self.reset(global_output_dir, &step.to_unique_str());
+            return;
+        }
+
+        let waves = crate::executor::job::topo_sort(&dirty).unwrap_or_else(|err| {
+            log::error!("{}", err);
+            std::process::exit(1);
+        });
+
         let package = config.get_package_from_step(step);
 
         let memory = config
@@ -137,46 +196,183 @@ impl ParallelExecutor {
             )
             .to_int();
 
-        match self.manager {
-            ParallelManager::Nextflow => {
-                // INFO: 'nextflow run <pipeline> -j <jobs>'
-                let runner = __get_assets_dir().join(NF_RUNNER);
-
-                let cmd = format!(
-                    "module load {} && nextflow run {} --jobs {} --mem {} --threads {}",
-                    package,
-                    runner.display(),
-                    jobs.display(),
-                    memory,
-                    threads,
-                );
+        // INFO: each wave is dispatched to completion before the next one
+        // is written out, so a job never starts before its prerequisites.
+        for wave in &waves {
+            let jobs = write_jobs(wave.clone(), global_output_dir.clone(), &step.to_unique_str());
 
-                std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(cmd)
-                    .output()
-                    .expect("ERROR: Failed to execute command");
-            }
-            ParallelManager::Para => {
-                // INFO: 'para make <step> <jobs> -q <queue> -memoryMb <memory>'
-                self.__para(
-                    config,
-                    &step.to_unique_str(),
-                    &jobs,
-                    threads as u32,
-                    memory as u32,
-                    package,
-                );
-            }
-            ParallelManager::Snakemake => {
-                todo!()
+            match self.manager {
+                ParallelManager::Nextflow => {
+                    // INFO: 'nextflow run <pipeline> -j <jobs>'
+                    let runner = __get_assets_dir().join(NF_RUNNER);
+
+                    let cmd = format!(
+                        "module load {} && nextflow run {} --jobs {} --mem {} --threads {}",
+                        package,
+                        runner.display(),
+                        jobs.display(),
+                        memory,
+                        threads,
+                    );
+
+                    let status = __stream_command(&cmd, self.jobserver.as_ref());
+
+                    if !status.success() {
+                        log::error!("ERROR: nextflow run exited with {}", status);
+                        std::process::exit(1);
+                    }
+                }
+                ParallelManager::Para => {
+                    // INFO: 'para make <step> <jobs> -q <queue> -memoryMb <memory>'
+                    self.__para(
+                        config,
+                        &step.to_unique_str(),
+                        &jobs,
+                        threads as u32,
+                        memory as u32,
+                        package.clone(),
+                    );
+                }
+                ParallelManager::Snakemake => {
+                    todo!()
+                }
+                ParallelManager::Local => {
+                    self.__local(&jobs, threads as usize);
+                }
             }
-            ParallelManager::Local => {
-                todo!()
+
+            // INFO: only reached once the manager arm above returned
+            // successfully, so every dispatched job in this wave is safe
+            // to mark fresh before moving on to its dependents.
+            for job in wave {
+                cache.record(job);
             }
         }
 
-        self.reset(global_output_dir);
+        cache.save(&global_output_dir);
+
+        self.reset(global_output_dir, &step.to_unique_str());
+    }
+
+    /// Run the jobs written to `jobs_file` concurrently on this machine,
+    /// bounded by a token-based job-server scheduler.
+    ///
+    /// A counting semaphore is seeded with `concurrency` tokens (capped
+    /// by the step's thread budget); each job acquires a token via a
+    /// blocking channel receive before spawning its command, and returns
+    /// the token on completion. All children are joined before returning.
+    ///
+    /// If `self.jobserver` is set, each job additionally acquires a token
+    /// from that global pool before the local semaphore one, so the total
+    /// number of external tool subprocesses never exceeds the `--jobs N`
+    /// budget even across concurrently-dispatched steps; the child also
+    /// gets `MAKEFLAGS` exported so it can hand sub-tokens to its own
+    /// children.
+    ///
+    /// # Arguments
+    ///
+    /// * `jobs_file` - Path to the newline-delimited jobs file written by `write_jobs`.
+    /// * `concurrency` - Maximum number of jobs running at once.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// executor.__local(&PathBuf::from("jobs"), 4);
+    /// ```
+    pub fn __local(&mut self, jobs_file: &PathBuf, concurrency: usize) {
+        use std::sync::mpsc::sync_channel;
+
+        let concurrency = concurrency.max(1).min(
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(concurrency.max(1)),
+        );
+
+        let commands = std::fs::read_to_string(jobs_file)
+            .expect("ERROR: Failed to read jobs file")
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        // INFO: seed the token pool with `concurrency` tokens up front.
+        let (tx, rx) = sync_channel::<()>(concurrency);
+        for _ in 0..concurrency {
+            tx.send(()).expect("ERROR: Failed to seed job-server tokens");
+        }
+        let rx = std::sync::Arc::new(std::sync::Mutex::new(rx));
+
+        let jobserver = self.jobserver;
+        let mut failures = Vec::new();
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = commands
+                .into_iter()
+                .map(|cmd| {
+                    let rx = std::sync::Arc::clone(&rx);
+                    let tx = tx.clone();
+
+                    scope.spawn(move || {
+                        // INFO: acquire the global token first, so a
+                        // process never even reserves a local slot while
+                        // the whole-DAG budget is exhausted.
+                        if let Some(jobserver) = &jobserver {
+                            jobserver
+                                .acquire()
+                                .expect("ERROR: Failed to acquire jobserver token");
+                        }
+
+                        // INFO: acquire a token before starting the process.
+                        rx.lock()
+                            .expect("ERROR: Poisoned job-server lock")
+                            .recv()
+                            .expect("ERROR: Job-server channel closed early");
+
+                        let mut command = std::process::Command::new("sh");
+                        command.arg("-c").arg(&cmd);
+
+                        if let Some(jobserver) = &jobserver {
+                            command.env(crate::jobserver::MAKEFLAGS, jobserver.makeflags());
+                        }
+
+                        let output = command
+                            .output()
+                            .expect("ERROR: Failed to execute local job");
+
+                        // INFO: return the token now that the slot is free.
+                        let _ = tx.send(());
+
+                        if let Some(jobserver) = &jobserver {
+                            jobserver
+                                .release()
+                                .expect("ERROR: Failed to release jobserver token");
+                        }
+
+                        (cmd, output)
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                let (cmd, output) = handle.join().expect("ERROR: Local job thread panicked");
+
+                if !output.status.success() {
+                    log::error!(
+                        "ERROR: local job failed: {}\n{}",
+                        cmd,
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                    failures.push(cmd);
+                } else {
+                    log::info!("INFO: local job completed: {}", cmd);
+                }
+            }
+        });
+
+        if !failures.is_empty() {
+            log::error!("ERROR: {} local job/s failed", failures.len());
+            std::process::exit(1);
+        }
     }
 
     /// Reset the executor by clearing the jobs and arguments
@@ -198,13 +394,16 @@ impl ParallelExecutor {
     /// assert_eq!(executor.jobs.len(), 0);
     /// assert_eq!(executor.args.len(), 0);
     /// ```
-    pub fn reset(&mut self, global_output_dir: PathBuf) {
+    pub fn reset(&mut self, global_output_dir: PathBuf, name: &str) {
         self.jobs.clear();
         self.args.clear();
 
-        // INFO: remove jobs file
-        let filename = global_output_dir.join("jobs");
-        std::fs::remove_file(&filename).expect("ERROR: Failed to remove job file");
+        // INFO: remove the jobs file, if one was ever written for this
+        // name (a step with no dirty jobs never writes one).
+        let filename = global_output_dir.join(format!("jobs.{}", name));
+        if filename.exists() {
+            std::fs::remove_file(&filename).expect("ERROR: Failed to remove job file");
+        }
     }
 
     /// Channels errors while using para as executor
@@ -300,8 +499,14 @@ impl ParallelExecutor {
     ) {
         match self.manager {
             ParallelManager::Para => {
-                let jobs = write_jobs(self.jobs.clone(), dir);
+                let jobs = write_jobs(self.jobs.clone(), dir, step);
                 self.__para(config, step, &jobs, threads, memory, package);
+                self.jobs.clear();
+            }
+            ParallelManager::Local => {
+                let jobs = write_jobs(self.jobs.clone(), dir, step);
+                self.__local(&jobs, threads as usize);
+                self.jobs.clear();
             }
             _ => {
                 todo!()
@@ -344,45 +549,156 @@ impl ParallelExecutor {
         let run_id = config.get_run_id();
         let step_code = format!("{}_{}", step, run_id);
 
-        let cmd = format!(
-            "module load {} && para make {} {} -q {} -memoryMb {} -numCores {}",
-            package,
-            step_code,
-            jobs.display(),
-            config
-                .global
-                .get(SHORT_QUEUE)
-                .expect("ERROR: No short queue found"),
-            memory * 1024, // WARN: Memory is in MB
-            threads,
-        );
+        let max_retries = config
+            .get_global_param(MAX_RETRIES)
+            .map(|p| p.to_int() as u32)
+            .unwrap_or(DEFAULT_MAX_RETRIES);
+        let backoff_ms = config
+            .get_global_param(RETRY_BACKOFF_MS)
+            .map(|p| p.to_int() as u64)
+            .unwrap_or(DEFAULT_RETRY_BACKOFF_MS);
 
-        log::info!("INFO: Executing command: {}", cmd);
+        let queue = config
+            .global
+            .get(SHORT_QUEUE)
+            .expect("ERROR: No short queue found");
 
-        let output = std::process::Command::new("sh")
-            .arg("-c")
-            .arg(cmd)
-            .output()
-            .expect("ERROR: Failed to execute command");
+        let mut jobs_file = jobs.clone();
+        let mut memory = memory;
+        let mut attempt = 0u32;
 
-        if !output.status.success() {
-            log::error!(
-                "ERROR: Failed to execute command: {}",
-                String::from_utf8_lossy(&output.stderr)
+        loop {
+            let cmd = format!(
+                "module load {} && para make {} {} -q {} -memoryMb {} -numCores {}",
+                package,
+                step_code,
+                jobs_file.display(),
+                queue,
+                memory * 1024, // WARN: Memory is in MB
+                threads,
             );
 
-            if let Ok(step) = PipelineStep::from_str(step) {
-                self.__channel_error(&step, run_id);
+            log::info!("INFO: Executing command: {}", cmd);
+
+            let (status, _stdout, stderr) = __stream_command_captured(&cmd, self.jobserver.as_ref());
+
+            if status.success() {
+                log::info!("INFO: Command executed successfully: {}", cmd);
+                return;
             }
 
-            std::process::exit(1);
-        } else {
-            log::info!(
-                "INFO: Command executed successfully: {}",
-                String::from_utf8_lossy(&output.stdout)
+            log::error!("ERROR: Failed to execute command: {}", stderr);
+
+            let crashed = self.__crashed_jobs(step, &run_id, &jobs_file);
+
+            if crashed.is_empty() || attempt >= max_retries {
+                if let Ok(step) = PipelineStep::from_str(step) {
+                    self.__channel_error(&step, run_id);
+                }
+                std::process::exit(1);
+            }
+
+            attempt += 1;
+
+            if crashed.iter().any(|job| job.is_oom) {
+                memory = (memory as f64 * MEMORY_ESCALATION_FACTOR).ceil() as u32;
+                log::warn!(
+                    "WARN: escalating memory to {}MB after an OOM-classified crash",
+                    memory * 1024
+                );
+            }
+
+            let backoff = backoff_ms * 2u64.pow(attempt - 1);
+            log::warn!(
+                "WARN: retrying {} crashed job/s (attempt {}/{}) after {}ms backoff",
+                crashed.len(),
+                attempt,
+                max_retries,
+                backoff
+            );
+            std::thread::sleep(std::time::Duration::from_millis(backoff));
+
+            jobs_file = write_jobs_from_cmds(
+                crashed.into_iter().map(|job| job.cmd).collect(),
+                &jobs_file,
             );
         }
     }
+
+    /// Collect every crashed job from the most recent `para` batch for
+    /// `step`/`run_id`, pairing each `.crashed` marker back to its
+    /// original command in `jobs_file` and classifying it as OOM if its
+    /// error text mentions memory exhaustion.
+    fn __crashed_jobs(&self, step: &str, run_id: &str, jobs_file: &PathBuf) -> Vec<CrashedJob> {
+        let dir = std::env::current_dir()
+            .expect("ERROR: Failed to get current directory!")
+            .join(".para")
+            .join(format!("{}_{}", step, run_id))
+            .join("1");
+
+        let mut crashed = Vec::new();
+
+        if !dir.exists() {
+            return crashed;
+        }
+
+        let commands = std::fs::read_to_string(jobs_file)
+            .unwrap_or_default()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        for entry in std::fs::read_dir(&dir)
+            .expect("ERROR: Failed to read directory")
+            .flatten()
+        {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            let Some(index) = name
+                .strip_suffix(".crashed")
+                .and_then(|stem| stem.parse::<usize>().ok())
+            else {
+                continue;
+            };
+
+            let Some(cmd) = commands.get(index.saturating_sub(1)) else {
+                continue;
+            };
+
+            let error = std::fs::read_to_string(entry.path()).unwrap_or_default();
+            let is_oom = ["oom", "out of memory", "memory limit", "killed"]
+                .iter()
+                .any(|needle| error.to_lowercase().contains(needle));
+
+            crashed.push(CrashedJob {
+                cmd: cmd.clone(),
+                is_oom,
+            });
+        }
+
+        crashed
+    }
+}
+
+/// A single `para` job that crashed in the previous batch, carrying
+/// enough to resubmit it: its original command and whether the crash
+/// looked memory-related.
+struct CrashedJob {
+    cmd: String,
+    is_oom: bool,
+}
+
+/// Overwrite `path` with `cmds`, one per line, and return it — used to
+/// rebuild a reduced jobs file containing only the crashed commands from
+/// a failed `para` batch.
+fn write_jobs_from_cmds(cmds: Vec<String>, path: &PathBuf) -> PathBuf {
+    let mut file = std::fs::File::create(path).expect("ERROR: Failed to create retry jobs file");
+    for cmd in cmds {
+        writeln!(file, "{}", cmd).expect("ERROR: Failed to write retry jobs file");
+    }
+
+    path.clone()
 }
 
 #[derive(Debug, Clone)]
@@ -580,6 +896,7 @@ impl ParallelManager {
             manager: self.clone(),
             jobs: Vec::new(),
             args: Vec::new(),
+            jobserver: None,
         }
     }
 }
@@ -596,22 +913,103 @@ impl ParallelManager {
 ///    Job::new("job2"),
 /// ];
 ///
-/// let filename = write_jobs(jobs);
+/// let filename = write_jobs(jobs, global_output_dir, "ccs");
 ///
-/// assert_eq!(filename.to_str().unwrap(), "jobs");
+/// assert_eq!(filename.to_str().unwrap(), "jobs.ccs");
 /// ```
-fn write_jobs(jobs: Vec<Job>, global_output_dir: PathBuf) -> PathBuf {
-    let filename = global_output_dir.join("jobs");
+fn write_jobs(jobs: Vec<Job>, global_output_dir: PathBuf, name: &str) -> PathBuf {
+    let filename = global_output_dir.join(format!("jobs.{}", name));
 
     let mut file = std::fs::File::create(&filename).expect("ERROR: Failed to create job file");
     for job in jobs {
-        let cmd = job.cmd;
-        writeln!(file, "{}", cmd).expect("ERROR: Failed to write to job file");
+        writeln!(file, "{}", job.render()).expect("ERROR: Failed to write to job file");
     }
 
     filename
 }
 
+/// Run `cmd` under `sh -c`, forwarding each line of stdout/stderr to the
+/// log as soon as it arrives instead of buffering until the child exits.
+///
+/// Stdout is forwarded via `log::info!`, stderr via `log::error!`, each on
+/// its own background thread so neither pipe can deadlock by filling its
+/// OS buffer while the other is being drained.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// let status = __stream_command("echo hi", None);
+/// assert!(status.success());
+/// ```
+pub fn __stream_command(cmd: &str, jobserver: Option<&JobServer>) -> std::process::ExitStatus {
+    let (status, _, _) = __stream_command_captured(cmd, jobserver);
+    status
+}
+
+/// Same as [`__stream_command`], but also returns the full accumulated
+/// stdout/stderr text so callers can include it in a final error report.
+///
+/// When `jobserver` is set, its `MAKEFLAGS`-style auth string is exported
+/// to the child so a jobserver-aware tool it invokes (e.g. a minimap2
+/// wrapper) cooperates with the same token pool instead of
+/// oversubscribing alongside it.
+fn __stream_command_captured(
+    cmd: &str,
+    jobserver: Option<&JobServer>,
+) -> (std::process::ExitStatus, String, String) {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let mut command = std::process::Command::new("sh");
+    command.arg("-c").arg(cmd);
+
+    if let Some(jobserver) = jobserver {
+        command.env(crate::jobserver::MAKEFLAGS, jobserver.makeflags());
+    }
+
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("ERROR: Failed to execute command");
+
+    let stdout = child.stdout.take().expect("ERROR: Child has no stdout");
+    let stderr = child.stderr.take().expect("ERROR: Child has no stderr");
+
+    let stdout_handle = std::thread::spawn(move || {
+        let mut tail = String::new();
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            log::info!("{}", line);
+            tail.push_str(&line);
+            tail.push('\n');
+        }
+        tail
+    });
+
+    let stderr_handle = std::thread::spawn(move || {
+        let mut tail = String::new();
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            log::error!("{}", line);
+            tail.push_str(&line);
+            tail.push('\n');
+        }
+        tail
+    });
+
+    let status = child
+        .wait()
+        .expect("ERROR: Failed to wait on child process");
+
+    let stdout_tail = stdout_handle
+        .join()
+        .expect("ERROR: stdout reader thread panicked");
+    let stderr_tail = stderr_handle
+        .join()
+        .expect("ERROR: stderr reader thread panicked");
+
+    (status, stdout_tail, stderr_tail)
+}
+
 /// Check if the parallel manager is valid
 ///
 /// # Example
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::executor::job::Job;
+
+/// Name of the freshness manifest written into `global_output_dir`.
+pub const CACHE_FILE: &str = ".isopipe-cache";
+
+/// A Fresh/Dirty build-cache keyed by a stable hash of a `Job`'s
+/// command, its input fingerprints, and its declared output.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let mut cache = FreshnessCache::load(&global_output_dir);
+/// let dirty = cache.partition(jobs);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FreshnessCache {
+    #[serde(default)]
+    entries: HashMap<String, String>,
+}
+
+impl FreshnessCache {
+    fn path(global_output_dir: &Path) -> PathBuf {
+        global_output_dir.join(CACHE_FILE)
+    }
+
+    /// Load the cache from `global_output_dir`, returning an empty one
+    /// if it hasn't been written yet.
+    pub fn load(global_output_dir: &Path) -> Self {
+        match std::fs::read_to_string(Self::path(global_output_dir)) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Persist the cache to `global_output_dir`.
+    pub fn save(&self, global_output_dir: &Path) {
+        let contents =
+            serde_json::to_string_pretty(self).expect("ERROR: Could not serialize freshness cache!");
+        std::fs::write(Self::path(global_output_dir), contents)
+            .expect("ERROR: Could not write .isopipe-cache!");
+    }
+
+    /// Split `jobs` into the ones that still need to run ("dirty") by
+    /// dropping any whose key matches the recorded one and whose output
+    /// already exists on disk ("fresh").
+    ///
+    /// # Returns
+    ///
+    /// The dirty jobs, in the same relative order they were given in.
+    pub fn partition(&self, jobs: Vec<Job>) -> Vec<Job> {
+        jobs.into_iter()
+            .filter(|job| {
+                let key = fingerprint(job);
+                let fresh = self.entries.get(&key).is_some_and(|recorded| {
+                    *recorded == key && job.outputs.iter().all(|o| o.exists())
+                });
+
+                if fresh {
+                    log::info!("INFO: skipping fresh job: {}", job.render());
+                }
+
+                !fresh
+            })
+            .collect()
+    }
+
+    /// Record `job` as fresh after it has succeeded.
+    pub fn record(&mut self, job: &Job) {
+        let key = fingerprint(job);
+        self.entries.insert(key.clone(), key);
+    }
+}
+
+/// Compute a stable content-addressed key for `job`: a BLAKE3 digest of
+/// its full command string plus a fingerprint of every declared input
+/// (file contents for small files, size+mtime as a cheap fallback for
+/// large ones) and its declared output path.
+fn fingerprint(job: &Job) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(job.render().as_bytes());
+
+    for input in &job.inputs {
+        hasher.update(input.to_string_lossy().as_bytes());
+
+        match std::fs::metadata(input) {
+            Ok(meta) if meta.len() <= 16 * 1024 * 1024 => {
+                if let Ok(contents) = std::fs::read(input) {
+                    hasher.update(&contents);
+                }
+            }
+            Ok(meta) => {
+                let mtime = meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+
+                hasher.update(format!("{}:{}", meta.len(), mtime).as_bytes());
+            }
+            Err(_) => {}
+        }
+    }
+
+    for output in &job.outputs {
+        hasher.update(output.to_string_lossy().as_bytes());
+    }
+
+    hasher.finalize().to_hex().to_string()
+}
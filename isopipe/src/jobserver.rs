@@ -0,0 +1,97 @@
+use std::io;
+use std::os::unix::io::RawFd;
+
+/// Name of the environment variable jobserver-aware child tools read,
+/// mirroring GNU make's `--jobserver-auth=R,W` protocol.
+pub const MAKEFLAGS: &str = "MAKEFLAGS";
+
+/// A GNU make-style token pool: a pipe preloaded with `jobs - 1` single
+/// byte tokens, plus the one implicit token every client already holds
+/// without acquiring it. Bounds the total number of concurrent external
+/// tool subprocesses across the whole step DAG, not just within a single
+/// step's own dispatch.
+///
+/// The read/write fds are inherited by spawned children (pipes created
+/// via `libc::pipe` aren't `CLOEXEC` by default), so exporting
+/// [`MAKEFLAGS`] lets a jobserver-aware wrapper cooperate with this pool
+/// instead of oversubscribing alongside it.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let pool = JobServer::new(8).expect("ERROR: Failed to create jobserver pipe");
+/// pool.acquire().expect("ERROR: Failed to acquire token");
+/// // ... run one external tool ...
+/// pool.release().expect("ERROR: Failed to release token");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct JobServer {
+    read_fd: RawFd,
+    write_fd: RawFd,
+}
+
+impl JobServer {
+    /// Create a new token pool sized for `jobs` total concurrent slots.
+    pub fn new(jobs: usize) -> io::Result<Self> {
+        let mut fds: [RawFd; 2] = [0, 0];
+
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let pool = Self {
+            read_fd: fds[0],
+            write_fd: fds[1],
+        };
+
+        // WARN: only jobs - 1 explicit tokens -> the caller itself holds
+        // the implicit token for its first concurrent slot.
+        for _ in 0..jobs.saturating_sub(1) {
+            pool.release()?;
+        }
+
+        Ok(pool)
+    }
+
+    /// Block until a token is available.
+    pub fn acquire(&self) -> io::Result<()> {
+        let mut byte = [0u8; 1];
+
+        loop {
+            let n = unsafe { libc::read(self.read_fd, byte.as_mut_ptr() as *mut _, 1) };
+
+            if n == 1 {
+                return Ok(());
+            }
+
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
+    /// Return a token to the pool.
+    pub fn release(&self) -> io::Result<()> {
+        let byte = [b'+'; 1];
+        let n = unsafe { libc::write(self.write_fd, byte.as_ptr() as *const _, 1) };
+
+        if n != 1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// The `MAKEFLAGS` value to export to a spawned child so it can find
+    /// and cooperate with this token pool.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// command.env(jobserver::MAKEFLAGS, pool.makeflags());
+    /// ```
+    pub fn makeflags(&self) -> String {
+        format!("--jobserver-auth={},{}", self.read_fd, self.write_fd)
+    }
+}
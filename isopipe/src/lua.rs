@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use rlua::{Lua, UserData, UserDataMethods};
+
+use crate::{
+    config::{Config, PipelineStep},
+    consts::*,
+    executor::job::Job,
+};
+
+/// Run a user-provided `.lua` script as a [`PipelineStep::Custom`] step.
+///
+/// The script sees a host API bound into its global scope:
+///
+/// * `input_dir`, `step_output_dir` - this step's resolved directories, as strings.
+/// * `config(key)` - this step's own config field (from `[params.custom]`), or `nil`.
+/// * `clustering_categories()` / `fusion_types()` - the same
+///   [`CLUSTERING_CATEGORIES`]/[`FUSION_TYPES`] constants the built-in
+///   steps use, as Lua arrays.
+/// * `job()` - a builder bound to the same [`Job`] surface as Rust code:
+///   `:program(name)`, `:arg(value)`, `:args({...})`, `:input(path)`,
+///   `:output(path)`, `:redirect_stdout(path)`, and `:submit()` to hand
+///   the finished job back to this run.
+///
+/// # Returns
+///
+/// Every job the script `:submit()`-ted, in the order submitted, handed
+/// back to [`crate::core::run_step`] exactly like a built-in step's jobs.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let jobs = lua::run_custom_step(&config, &input_dir, &step_output_dir, &script);
+/// ```
+pub fn run_custom_step(
+    config: &Config,
+    input_dir: &Path,
+    step_output_dir: &Path,
+    script: &Path,
+) -> Vec<Job> {
+    let source = std::fs::read_to_string(script)
+        .unwrap_or_else(|e| panic!("ERROR: Could not read custom step script '{}': {}", script.display(), e));
+
+    let jobs: Rc<RefCell<Vec<Job>>> = Rc::new(RefCell::new(Vec::new()));
+    let lua = Lua::new();
+
+    lua.context(|ctx| {
+        let globals = ctx.globals();
+
+        globals
+            .set("input_dir", input_dir.display().to_string())
+            .expect("ERROR: Failed to bind input_dir into Lua globals");
+        globals
+            .set("step_output_dir", step_output_dir.display().to_string())
+            .expect("ERROR: Failed to bind step_output_dir into Lua globals");
+
+        let config = config.clone();
+        let get_config = ctx
+            .create_function(move |_, key: String| {
+                Ok(config
+                    .get_param(PipelineStep::Custom, &key)
+                    .map(|value| value.to_string()))
+            })
+            .expect("ERROR: Failed to create Lua config() host function");
+        globals
+            .set("config", get_config)
+            .expect("ERROR: Failed to bind config() into Lua globals");
+
+        let clustering_categories = ctx
+            .create_function(|ctx, ()| ctx.create_sequence_from(CLUSTERING_CATEGORIES.iter().copied()))
+            .expect("ERROR: Failed to create Lua clustering_categories() host function");
+        globals
+            .set("clustering_categories", clustering_categories)
+            .expect("ERROR: Failed to bind clustering_categories() into Lua globals");
+
+        let fusion_types = ctx
+            .create_function(|ctx, ()| ctx.create_sequence_from(FUSION_TYPES.iter().copied()))
+            .expect("ERROR: Failed to create Lua fusion_types() host function");
+        globals
+            .set("fusion_types", fusion_types)
+            .expect("ERROR: Failed to bind fusion_types() into Lua globals");
+
+        let new_job_jobs = Rc::clone(&jobs);
+        let new_job = ctx
+            .create_function(move |_, ()| {
+                Ok(LuaJob {
+                    job: RefCell::new(Job::new()),
+                    jobs: Rc::clone(&new_job_jobs),
+                })
+            })
+            .expect("ERROR: Failed to create Lua job() host function");
+        globals
+            .set("job", new_job)
+            .expect("ERROR: Failed to bind job() into Lua globals");
+
+        ctx.load(&source)
+            .set_name(&script.display().to_string())
+            .unwrap_or_else(|e| panic!("ERROR: Failed to name custom step script: {}", e))
+            .exec()
+            .unwrap_or_else(|e| {
+                panic!(
+                    "ERROR: Custom step script '{}' failed: {}",
+                    script.display(),
+                    e
+                )
+            });
+    });
+
+    Rc::try_unwrap(jobs)
+        .expect("ERROR: Custom step script leaked a reference to its job collector")
+        .into_inner()
+}
+
+/// A [`Job`] under construction from Lua. Every method mutates the
+/// wrapped job in place and returns nothing, so calls are not chained:
+/// scripts make one call per statement on the same job value;
+/// `:submit()` hands the finished job to the collector shared with
+/// [`run_custom_step`].
+struct LuaJob {
+    job: RefCell<Job>,
+    jobs: Rc<RefCell<Vec<Job>>>,
+}
+
+impl UserData for LuaJob {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("program", |_, this, name: String| {
+            this.job.replace_with(|job| std::mem::take(job).program(name));
+            Ok(())
+        });
+
+        methods.add_method("arg", |_, this, value: String| {
+            this.job.replace_with(|job| std::mem::take(job).arg(value));
+            Ok(())
+        });
+
+        methods.add_method("args", |_, this, values: Vec<String>| {
+            let values: Vec<&str> = values.iter().map(String::as_str).collect();
+            this.job.replace_with(|job| std::mem::take(job).args(&values));
+            Ok(())
+        });
+
+        methods.add_method("input", |_, this, path: String| {
+            this.job.replace_with(|job| std::mem::take(job).input(PathBuf::from(path)));
+            Ok(())
+        });
+
+        methods.add_method("output", |_, this, path: String| {
+            this.job.replace_with(|job| std::mem::take(job).output(PathBuf::from(path)));
+            Ok(())
+        });
+
+        methods.add_method("redirect_stdout", |_, this, path: String| {
+            this.job
+                .replace_with(|job| std::mem::take(job).redirect_stdout(PathBuf::from(path)));
+            Ok(())
+        });
+
+        methods.add_method("submit", |_, this, ()| {
+            this.jobs.borrow_mut().push(this.job.borrow().clone());
+            Ok(())
+        });
+    }
+}
@@ -14,7 +14,11 @@ fn main() {
 
     let args: Args = Args::parse();
 
-    let executor = match args.manager {
+    let mut executor = match args.manager {
+        ParallelManager::Local => {
+            info!("INFO: Initializing local executor...");
+            args.manager.init()
+        }
         ParallelManager::Nextflow | ParallelManager::Para => {
             info!("INFO: Initializing parallel executor...");
             args.manager.init()
@@ -25,6 +29,14 @@ fn main() {
         }
     };
 
+    // INFO: `--jobs 0` (the default) means no global ceiling: each manager
+    // keeps bounding concurrency on its own, same as before this flag existed.
+    if args.jobs > 0 {
+        let jobserver = isopipe::jobserver::JobServer::new(args.jobs)
+            .expect("ERROR: Failed to create jobserver token pool");
+        executor.with_jobserver(jobserver);
+    }
+
     match args.command {
         SubArgs::Run { args } => {
             // args.check().unwrap_or_else(|e| {
@@ -34,14 +46,50 @@ fn main() {
 
             let config = isopipe::config::Config::read(args.config)
                 .expect("ERROR: Could not read config file");
+
+            if let Err(errors) = config.validate() {
+                for err in &errors {
+                    error!("{}", err);
+                }
+                error!(
+                    "ERROR: {} problem/s found in config.toml, aborting before any step runs!",
+                    errors.len()
+                );
+                std::process::exit(1);
+            }
+
             config.load().expect("ERROR: Could not load config file");
 
-            let global_output_dir = config.create_global_output_dir();
+            match args.resume {
+                Some(output_dir) => {
+                    let remaining = config.resume(&output_dir).unwrap_or_else(|e| {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    });
 
-            run(config, global_output_dir, executor).unwrap_or_else(|e| {
-                error!("{}", e);
-                std::process::exit(1);
-            });
+                    info!(
+                        "INFO: resuming run at {} with {} step/s remaining...",
+                        output_dir.display(),
+                        remaining.len()
+                    );
+
+                    isopipe::core::run_steps(
+                        remaining,
+                        &config,
+                        &output_dir,
+                        &mut executor,
+                        args.force,
+                    );
+                }
+                None => {
+                    let global_output_dir = config.create_global_output_dir();
+
+                    run(config, global_output_dir, executor, args.force).unwrap_or_else(|e| {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    });
+                }
+            }
         }
         SubArgs::Step { args } => {
             // args.check().unwrap_or_else(|e| {
@@ -57,9 +105,66 @@ fn main() {
                 .load()
                 .expect("ERROR: Could not load config file");
 
+            match args.resume.clone() {
+                Some(output_dir) => {
+                    let remaining = config.resume(&output_dir).unwrap_or_else(|e| {
+                        error!("{}", e);
+                        std::process::exit(1);
+                    });
+
+                    info!(
+                        "INFO: resuming run at {} with {} step/s remaining...",
+                        output_dir.display(),
+                        remaining.len()
+                    );
+
+                    isopipe::core::run_steps(
+                        remaining,
+                        &config,
+                        &output_dir,
+                        &mut executor,
+                        args.force,
+                    );
+                }
+                None => {
+                    let global_output_dir = config.create_global_output_dir();
+
+                    isopipe::core::run_steps(
+                        config.steps().clone(),
+                        &config,
+                        &global_output_dir,
+                        &mut executor,
+                        args.force,
+                    );
+                }
+            }
+        }
+        SubArgs::Watch { args } => {
+            let mut config = isopipe::config::Config::read(args.config)
+                .expect("ERROR: Could not read config file");
+
+            if let Err(errors) = config.validate() {
+                for err in &errors {
+                    error!("{}", err);
+                }
+                error!(
+                    "ERROR: {} problem/s found in config.toml, aborting before watching starts!",
+                    errors.len()
+                );
+                std::process::exit(1);
+            }
+
+            config.load().expect("ERROR: Could not load config file");
+
             let global_output_dir = config.create_global_output_dir();
 
-            run(config, global_output_dir, executor).unwrap_or_else(|e| {
+            let result = if args.new_inputs {
+                isopipe::watch::run_new_inputs(config, global_output_dir, executor, args.debounce)
+            } else {
+                isopipe::watch::run(config, global_output_dir, executor, args.debounce)
+            };
+
+            result.unwrap_or_else(|e| {
                 error!("{}", e);
                 std::process::exit(1);
             });
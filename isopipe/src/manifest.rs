@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, PipelineStep};
+
+/// Name of the directory (relative to the run's output root) that holds
+/// the resumable-run checkpoint manifest.
+pub const MANIFEST_DIR: &str = ".isopipe";
+
+/// Name of the checkpoint manifest file inside [`MANIFEST_DIR`].
+pub const MANIFEST_FILE: &str = "manifest.toml";
+
+/// A single checkpoint record for a completed `PipelineStep`.
+///
+/// # Fields
+///
+/// * `digest` - A hash over the step's sorted input fingerprints, its
+///   resolved args/custom fields, and the tool version.
+/// * `outputs` - The declared output paths that must exist for the
+///   checkpoint to be considered valid.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Checkpoint {
+    pub digest: String,
+    pub outputs: Vec<PathBuf>,
+}
+
+/// On-disk manifest recording one [`Checkpoint`] per `PipelineStep` that
+/// has completed successfully.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let manifest = Manifest::load(&global_output_dir);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Manifest {
+    #[serde(default)]
+    steps: HashMap<String, Checkpoint>,
+}
+
+impl Manifest {
+    /// Path to the manifest file for a given run's output root.
+    fn path(global_output_dir: &Path) -> PathBuf {
+        global_output_dir.join(MANIFEST_DIR).join(MANIFEST_FILE)
+    }
+
+    /// Load the manifest from disk, returning an empty manifest if it
+    /// does not exist yet.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// let manifest = Manifest::load(&global_output_dir);
+    /// ```
+    pub fn load(global_output_dir: &Path) -> Self {
+        let path = Self::path(global_output_dir);
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Persist the manifest to disk, creating [`MANIFEST_DIR`] if needed.
+    ///
+    /// # Example
+    ///
+    /// ``` rust, no_run
+    /// manifest.save(&global_output_dir);
+    /// ```
+    pub fn save(&self, global_output_dir: &Path) {
+        let dir = global_output_dir.join(MANIFEST_DIR);
+        std::fs::create_dir_all(&dir).expect("ERROR: Could not create .isopipe directory!");
+
+        let contents = toml::to_string_pretty(self).expect("ERROR: Could not serialize manifest!");
+        std::fs::write(Self::path(global_output_dir), contents)
+            .expect("ERROR: Could not write manifest.toml!");
+    }
+
+    /// Record a checkpoint for `step` after its jobs have succeeded.
+    ///
+    /// This must only be called once the executor has verified the
+    /// step's exit status, so a process killed mid-step never gets
+    /// recorded as a cache hit.
+    pub fn record(&mut self, step: &PipelineStep, digest: String, outputs: Vec<PathBuf>) {
+        self.steps
+            .insert(step.to_unique_str(), Checkpoint { digest, outputs });
+    }
+
+    /// Invalidate the checkpoint for `step`, if any.
+    ///
+    /// Used to drop a downstream step's cache entry whenever an
+    /// upstream step re-runs, so a stale digest can never mask a fresh
+    /// miss further down the pipeline.
+    pub fn invalidate(&mut self, step: &PipelineStep) {
+        self.steps.remove(&step.to_unique_str());
+    }
+
+    /// Check whether `step` is fresh: its stored digest matches
+    /// `digest` and every declared output still exists on disk.
+    pub fn is_fresh(&self, step: &PipelineStep, digest: &str) -> bool {
+        match self.steps.get(&step.to_unique_str()) {
+            Some(checkpoint) => {
+                checkpoint.digest == digest && checkpoint.outputs.iter().all(|p| p.exists())
+            }
+            None => false,
+        }
+    }
+}
+
+/// Filter `steps` down to the ones that actually need to run, dropping
+/// any step whose checkpoint digest matches the current inputs/args and
+/// whose declared outputs are still present on disk.
+///
+/// A cache hit for step N must never mask a miss for step N-1: as soon
+/// as one step is found dirty, every step after it is treated as dirty
+/// too, since its inputs may depend on the now-stale upstream output.
+///
+/// # Arguments
+///
+/// * `steps` - The steps selected by `StepArgs::abs_steps`.
+/// * `manifest` - The loaded checkpoint manifest.
+/// * `digests` - A digest for each step in `steps`, computed up front
+///   (e.g. via [`compute_digest`]) so input directories can still be
+///   resolved before any step actually runs.
+/// * `force` - When true, bypass the manifest entirely.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let steps = filter_fresh_steps(steps, &manifest, &digests, args.force);
+/// ```
+pub fn filter_fresh_steps(
+    steps: Vec<PipelineStep>,
+    manifest: &Manifest,
+    digests: &HashMap<String, String>,
+    force: bool,
+) -> Vec<PipelineStep> {
+    if force {
+        return steps;
+    }
+
+    let mut upstream_dirty = false;
+
+    steps
+        .into_iter()
+        .filter(|step| {
+            if upstream_dirty {
+                return true;
+            }
+
+            let digest = digests.get(&step.to_unique_str());
+            let fresh = digest.is_some_and(|d| manifest.is_fresh(step, d));
+
+            if fresh {
+                log::info!("INFO: skipping {} (up-to-date)...", step);
+            } else {
+                upstream_dirty = true;
+            }
+
+            !fresh
+        })
+        .collect()
+}
+
+/// Compute a stable digest for `step` over its sorted input file
+/// fingerprints (path + mtime + size), its resolved args/custom fields,
+/// and the tool version.
+///
+/// # Arguments
+///
+/// * `step` - The step the digest is being computed for.
+/// * `config` - The pipeline configuration.
+/// * `input_dir` - The directory the step reads its inputs from.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// let digest = compute_digest(&PipelineStep::Ccs, &config, &input_dir);
+/// ```
+pub fn compute_digest(step: &PipelineStep, config: &Config, input_dir: &Path) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut fingerprints = Vec::new();
+
+    if let Ok(entries) = std::fs::read_dir(input_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Ok(meta) = entry.metadata() {
+                let mtime = meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+
+                fingerprints.push(format!("{}:{}:{}", path.display(), mtime, meta.len()));
+            }
+        }
+    }
+    fingerprints.sort();
+
+    let args = config.get_step_args(step, Vec::new());
+
+    let mut hasher = DefaultHasher::new();
+    fingerprints.hash(&mut hasher);
+    args.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+
+    format!("{:016x}", hasher.finish())
+}
@@ -0,0 +1,273 @@
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::{config::*, core, executor::manager::ParallelExecutor};
+
+/// Watch every step's `input_dir` and, on a settled batch of changes,
+/// re-run the steps that consume the changed paths plus everything
+/// downstream of them.
+///
+/// # Arguments
+///
+/// * `config` - The pipeline configuration.
+/// * `global_output_dir` - The run's output root (writes under here are ignored).
+/// * `executor` - The executor used to dispatch the triggered `run-step`.
+/// * `debounce_ms` - Milliseconds to coalesce filesystem events over.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// watch::run(config, global_output_dir, executor, 200).unwrap();
+/// ```
+pub fn run(
+    config: Config,
+    global_output_dir: PathBuf,
+    mut executor: ParallelExecutor,
+    debounce_ms: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (tx, rx) = channel();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    })?;
+
+    for step in config.steps() {
+        let (input_dir, _) = config.get_step_dirs(step, &global_output_dir);
+        if input_dir.exists() {
+            watcher.watch(&input_dir, RecursiveMode::Recursive)?;
+        }
+    }
+
+    log::info!("INFO: watching input directories for changes (Ctrl-C to stop)...");
+
+    let running = Arc::new(AtomicBool::new(false));
+    let queued = Arc::new(Mutex::new(false));
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+
+    loop {
+        match rx.recv_timeout(Duration::from_millis(debounce_ms)) {
+            Ok(event) => {
+                for path in event.paths {
+                    // WARN: ignore writes inside our own output directories
+                    // to avoid triggering ourselves in an infinite loop.
+                    if path.starts_with(&global_output_dir) {
+                        continue;
+                    }
+                    pending.insert(path);
+                }
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if pending.is_empty() {
+                    continue;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let batch = std::mem::take(&mut pending);
+        let affected = affected_steps(&config, &global_output_dir, &batch);
+
+        if affected.is_empty() {
+            continue;
+        }
+
+        if running.load(Ordering::SeqCst) {
+            // INFO: a run is already in flight; queue exactly one follow-up.
+            *queued.lock().expect("ERROR: poisoned queue lock") = true;
+            continue;
+        }
+
+        trigger(&config, &global_output_dir, &mut executor, &affected, &running);
+
+        while std::mem::take(&mut *queued.lock().expect("ERROR: poisoned queue lock")) {
+            trigger(&config, &global_output_dir, &mut executor, &affected, &running);
+        }
+    }
+
+    Ok(())
+}
+
+/// Determine which steps (and everything downstream of them) consume at
+/// least one of the changed `paths`.
+fn affected_steps(
+    config: &Config,
+    global_output_dir: &PathBuf,
+    paths: &HashSet<PathBuf>,
+) -> Vec<PipelineStep> {
+    let mut min_affected = None;
+
+    for step in config.steps() {
+        let (input_dir, _) = config.get_step_dirs(step, global_output_dir);
+
+        let touches = paths.iter().any(|p| p.starts_with(&input_dir));
+        if touches {
+            min_affected = Some(match min_affected {
+                Some(current) if current <= step.to_int() => current,
+                _ => step.to_int(),
+            });
+        }
+    }
+
+    match min_affected {
+        Some(floor) => config
+            .steps()
+            .iter()
+            .filter(|s| s.to_int() >= floor)
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Watch only the first step's `input_dir` for newly-arrived BAM files
+/// (movies landing from a streaming/rolling sequencing run) and, on each
+/// settled batch, dispatch CCS jobs for just the new files rather than
+/// re-running the whole pipeline.
+///
+/// Files present before the watcher started are treated as already
+/// processed. The freshness cache in [`crate::executor::manager`] still
+/// applies underneath, so a file that somehow gets reported twice is a
+/// no-op, not a reprocess.
+///
+/// # Example
+///
+/// ``` rust, no_run
+/// watch::run_new_inputs(config, global_output_dir, executor, 200).unwrap();
+/// ```
+pub fn run_new_inputs(
+    config: Config,
+    global_output_dir: PathBuf,
+    mut executor: ParallelExecutor,
+    debounce_ms: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let step = *config
+        .steps()
+        .first()
+        .expect("ERROR: no steps configured to watch");
+    let (input_dir, step_output_dir) = config.get_step_dirs(&step, &global_output_dir);
+
+    let mut seen: HashSet<PathBuf> = list_bams(&input_dir);
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    })?;
+
+    watcher.watch(&input_dir, RecursiveMode::NonRecursive)?;
+
+    log::info!(
+        "INFO: watching {} for new BAM files (Ctrl-C to stop)...",
+        input_dir.display()
+    );
+
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+
+    loop {
+        match rx.recv_timeout(Duration::from_millis(debounce_ms)) {
+            Ok(event) => {
+                for path in event.paths {
+                    if is_bam(&path) && !seen.contains(&path) {
+                        pending.insert(path);
+                    }
+                }
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if pending.is_empty() {
+                    continue;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        // WARN: only files still on disk count as "arrived" -> a rename
+        // or delete racing the debounce window drops out here.
+        let new_bams: Vec<PathBuf> = std::mem::take(&mut pending)
+            .into_iter()
+            .filter(|p| p.exists())
+            .collect();
+
+        if new_bams.is_empty() {
+            continue;
+        }
+
+        for bam in &new_bams {
+            seen.insert(bam.clone());
+        }
+
+        log::info!(
+            "INFO: {} new BAM file/s arrived -> submitting CCS jobs: {:?}",
+            new_bams.len(),
+            new_bams
+        );
+
+        let jobs = crate::core::ccs::ccs_for_bams(
+            &step,
+            &config,
+            &new_bams,
+            &step_output_dir,
+            config.get_data_prefix(),
+            &mut executor,
+        );
+
+        executor
+            .add_jobs(jobs)
+            .execute(&config, &step, global_output_dir.clone());
+    }
+
+    Ok(())
+}
+
+/// List every BAM file currently in `dir` (non-recursive).
+fn list_bams(dir: &PathBuf) -> HashSet<PathBuf> {
+    if !dir.exists() {
+        return HashSet::new();
+    }
+
+    std::fs::read_dir(dir)
+        .expect("ERROR: Failed to read input directory")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_bam(path))
+        .collect()
+}
+
+fn is_bam(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case(crate::consts::BAM))
+        .unwrap_or(false)
+}
+
+/// Clear the terminal, print a concise summary of the triggered rebuild,
+/// and run the affected steps.
+fn trigger(
+    config: &Config,
+    global_output_dir: &PathBuf,
+    executor: &mut ParallelExecutor,
+    steps: &[PipelineStep],
+    running: &Arc<AtomicBool>,
+) {
+    running.store(true, Ordering::SeqCst);
+
+    print!("\x1B[2J\x1B[1;1H");
+    log::info!(
+        "INFO: input change detected -> re-running {} step/s: {:?}",
+        steps.len(),
+        steps.iter().map(|s| s.to_unique_str()).collect::<Vec<_>>()
+    );
+
+    core::run_steps(steps.to_vec(), config, global_output_dir, executor, true);
+
+    running.store(false, Ordering::SeqCst);
+}